@@ -0,0 +1,433 @@
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::config::AppConfig;
+use crate::countries::CountryRegistry;
+use crate::diff::get_users_diff;
+use crate::generator::{self, GeneratorConfig, GeneratorFormat};
+use crate::leaderboard::get_leaderboard;
+use crate::negotiation::ResponseFormat;
+use crate::parallel::Parallelism;
+use crate::quality::get_data_quality;
+use crate::regions::RegionMap;
+use crate::{
+    Root, User, build_team_insights, count_countries, count_logins_per_day,
+    get_active_users_per_day, get_superusers, get_team_insights, get_topcountries, ingest,
+};
+
+/* Benchmark do ingest e das agregações.
+ *
+ * Pra cada tamanho (10k, 100k, 1MM por padrão) gera um dataset com o
+ * `generator.rs` e mede, N vezes cada:
+ *
+ * - ingest: o mesmo caminho do `POST /users` (parse, países, colunar)
+ * - aggregate/...: cada agregação do zero, sem o cache
+ * - handler/...: os handlers de verdade, com o cache já quente, mais o
+ *   `serde_json::to_vec` da resposta (é o custo de um request "normal").
+ *   O `/users/diff` roda depois de um segundo upload, com ~5% dos
+ *   usuários removidos, ~10% alterados e ~5% novos
+ *
+ * Memória: o binário `bench` instala o `TrackingAllocator`, que conta
+ * os bytes alocados. O pico é o máximo acima do que já estava alocado
+ * quando o passo começou; o "retido" é o que sobrou vivo no final (pro
+ * ingest, é o tamanho do dataset). Sem o allocator os dois ficam `null`.
+ *
+ *   $ cargo run --release --bin bench -- --sizes 10000,100000 --format json > bench.json
+ *   $ cargo run --release --bin bench -- --baseline bench.json
+ */
+pub struct TrackingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn track_alloc(size: usize) {
+    let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                track_alloc(new_size - layout.size());
+            } else {
+                ALLOCATED.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+// Com o allocator instalado, a essa altura já tem alguma coisa alocada
+fn tracking() -> bool {
+    ALLOCATED.load(Ordering::Relaxed) > 0
+}
+
+pub struct BenchOptions {
+    pub sizes: Vec<usize>,
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StepResult {
+    name: String,
+    mean_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+    peak_bytes: Option<usize>,
+    retained_bytes: Option<isize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SizeResult {
+    users: usize,
+    input_bytes: usize,
+    steps: Vec<StepResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BenchReport {
+    seed: u64,
+    iterations: usize,
+    threads: usize,
+    sizes: Vec<SizeResult>,
+}
+
+fn measure<T>(name: &str, iterations: usize, mut step: impl FnMut() -> T) -> StepResult {
+    let mut times = Vec::with_capacity(iterations);
+    let mut peak: Option<usize> = None;
+    let mut retained: Option<isize> = None;
+
+    for _ in 0..iterations.max(1) {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+
+        let start = Instant::now();
+        let output = step();
+        let elapsed = start.elapsed();
+
+        // Antes do drop: o que o passo devolveu conta como retido
+        if tracking() {
+            let after = ALLOCATED.load(Ordering::Relaxed);
+            let step_peak = PEAK.load(Ordering::Relaxed).saturating_sub(before);
+
+            peak = Some(peak.unwrap_or(0).max(step_peak));
+            retained = Some(after as isize - before as isize);
+        }
+
+        drop(output);
+        times.push(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    StepResult {
+        name: name.to_owned(),
+        mean_ms: times.iter().sum::<f64>() / times.len() as f64,
+        min_ms: times.iter().copied().fold(f64::INFINITY, f64::min),
+        max_ms: times.iter().copied().fold(0.0, f64::max),
+        peak_bytes: peak,
+        retained_bytes: retained,
+    }
+}
+
+fn generate_input(seed: u64, count: usize) -> Result<String, String> {
+    let config = GeneratorConfig {
+        seed,
+        count,
+        ..Default::default()
+    };
+
+    let mut input = Vec::new();
+    generator::generate(&config, GeneratorFormat::Json, false, &mut input)
+        .map_err(|e| e.to_string())?;
+    String::from_utf8(input).map_err(|e| e.to_string())
+}
+
+// A "próxima versão" do export, pro diff ter o que comparar
+fn next_version_input(input: &str, seed: u64) -> Result<String, String> {
+    let users: Vec<User> = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    let added: Vec<User> = serde_json::from_str(&generate_input(
+        seed.wrapping_add(1),
+        users.len().div_ceil(20),
+    )?)
+    .map_err(|e| e.to_string())?;
+
+    let next: Vec<User> = users
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % 20 != 19)
+        .map(|(i, mut user)| {
+            if i % 10 == 0 {
+                user.score = user.score.saturating_add(7);
+            }
+            user
+        })
+        .chain(added)
+        .collect();
+
+    serde_json::to_string(&next).map_err(|e| e.to_string())
+}
+
+fn bench_size(
+    users: usize,
+    options: &BenchOptions,
+    config: &AppConfig,
+    countries: &CountryRegistry,
+    parallelism: &Parallelism,
+    region_map: &RegionMap,
+) -> Result<SizeResult, String> {
+    let input = generate_input(options.seed, users)?;
+    let input_bytes = input.len();
+    let next_input = next_version_input(&input, options.seed)?;
+
+    let iterations = options.iterations;
+    let mut steps = Vec::new();
+
+    steps.push(measure("ingest", iterations, || {
+        let root = Root::new();
+        ingest(&input, &root, countries).map(|_| root)
+    }));
+
+    let root = Root::new();
+    ingest(&input, &root, countries).map_err(|e| e.to_string())?;
+    drop(input);
+
+    let dataset = root.snapshot();
+    let columns = &dataset.columns;
+
+    steps.push(measure("aggregate/countries", iterations, || {
+        count_countries(columns, parallelism)
+    }));
+    steps.push(measure("aggregate/teams", iterations, || {
+        build_team_insights(columns, parallelism)
+    }));
+    steps.push(measure("aggregate/logins", iterations, || {
+        count_logins_per_day(columns, parallelism)
+    }));
+
+    // Cache quente: o custo de um request depois do primeiro
+    dataset.aggregates(parallelism);
+
+    let root_state = <&State<Root>>::from(&root);
+    let parallelism_state = <&State<Parallelism>>::from(parallelism);
+    let config_state = <&State<AppConfig>>::from(config);
+
+    // Serializa junto: montar a struct sem o JSON não é o custo do request
+    steps.push(measure("handler/superusers", iterations, || {
+        let resp = get_superusers(ResponseFormat::Json, root_state)
+            .right()
+            .unwrap();
+        serde_json::to_vec(&resp.0)
+    }));
+    steps.push(measure("handler/top-countries", iterations, || {
+        let resp = get_topcountries(
+            None,
+            ResponseFormat::Json,
+            root_state,
+            region_map.into(),
+            parallelism_state,
+        );
+        serde_json::to_vec(&resp.0)
+    }));
+    steps.push(measure("handler/team-insights", iterations, || {
+        let resp = get_team_insights(ResponseFormat::Json, root_state, parallelism_state);
+        serde_json::to_vec(&resp.0)
+    }));
+    steps.push(measure("handler/active-users-per-day", iterations, || {
+        let resp =
+            get_active_users_per_day(None, ResponseFormat::Json, root_state, parallelism_state);
+        serde_json::to_vec(&resp.0)
+    }));
+    steps.push(measure("handler/leaderboard", iterations, || {
+        let resp = get_leaderboard(None, None, None, root_state);
+        serde_json::to_vec(&resp.0)
+    }));
+    steps.push(measure("handler/data-quality", iterations, || {
+        let resp = get_data_quality(root_state, config_state);
+        serde_json::to_vec(&resp.0)
+    }));
+
+    drop(dataset);
+    ingest(&next_input, &root, countries).map_err(|e| e.to_string())?;
+    drop(next_input);
+
+    steps.push(measure("handler/users-diff", iterations, || {
+        let resp = get_users_diff(None, None, root_state, parallelism_state).unwrap();
+        serde_json::to_vec(&resp.0)
+    }));
+
+    Ok(SizeResult {
+        users,
+        input_bytes,
+        steps,
+    })
+}
+
+/* `progress` recebe uma linha por tamanho, pra quem estiver rodando
+ * 1MM de usuários saber que não travou.
+ */
+pub fn run(options: &BenchOptions, progress: impl Fn(&str)) -> Result<BenchReport, String> {
+    let config = AppConfig::from_rocket_figment()?;
+
+    let countries = match &config.countries.alias_file {
+        Some(path) => CountryRegistry::builtin().with_alias_file(path)?,
+        None => CountryRegistry::builtin(),
+    };
+    let parallelism = Parallelism::from_config(&config.parallel)?;
+    let region_map = RegionMap::from_config(&config.regions)?;
+
+    let mut sizes = Vec::with_capacity(options.sizes.len());
+
+    for &users in options.sizes.iter() {
+        progress(&format!("benchmarking {} users...", users));
+        sizes.push(bench_size(
+            users,
+            options,
+            &config,
+            &countries,
+            &parallelism,
+            &region_map,
+        )?);
+    }
+
+    Ok(BenchReport {
+        seed: options.seed,
+        iterations: options.iterations.max(1),
+        threads: parallelism.threads(),
+        sizes,
+    })
+}
+
+fn format_bytes(bytes: Option<isize>) -> String {
+    let Some(bytes) = bytes else {
+        return String::from("-");
+    };
+
+    let abs = bytes.unsigned_abs() as f64;
+    let sign = if bytes < 0 { "-" } else { "" };
+
+    match abs {
+        b if b >= 1024.0 * 1024.0 * 1024.0 => {
+            format!("{}{:.2} GiB", sign, b / 1024.0 / 1024.0 / 1024.0)
+        }
+        b if b >= 1024.0 * 1024.0 => format!("{}{:.1} MiB", sign, b / 1024.0 / 1024.0),
+        b if b >= 1024.0 => format!("{}{:.1} KiB", sign, b / 1024.0),
+        b => format!("{}{} B", sign, b),
+    }
+}
+
+// Variação do tempo médio contra um relatório salvo (`--format json`)
+fn change_vs(baseline: Option<&BenchReport>, users: usize, step: &StepResult) -> String {
+    let before = baseline
+        .and_then(|b| b.sizes.iter().find(|s| s.users == users))
+        .and_then(|s| s.steps.iter().find(|b| b.name == step.name));
+
+    match before {
+        Some(before) if before.mean_ms > 0.0 => {
+            format!("{:+.1}%", (step.mean_ms / before.mean_ms - 1.0) * 100.0)
+        }
+        _ => String::from("-"),
+    }
+}
+
+pub fn table(report: &BenchReport, baseline: Option<&BenchReport>) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "seed {} - {} iteration(s) - {} thread(s)",
+        report.seed, report.iterations, report.threads
+    );
+
+    for size in report.sizes.iter() {
+        let _ = writeln!(out, "\n{} users", size.users);
+        let _ = writeln!(
+            out,
+            "  {:<30} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10}",
+            "STEP", "MEAN (ms)", "MIN (ms)", "MAX (ms)", "PEAK", "RETAINED", "VS BASE"
+        );
+
+        for step in size.steps.iter() {
+            let _ = writeln!(
+                out,
+                "  {:<30} {:>12.2} {:>12.2} {:>12.2} {:>12} {:>12} {:>10}",
+                step.name,
+                step.mean_ms,
+                step.min_ms,
+                step.max_ms,
+                format_bytes(step.peak_bytes.map(|b| b as isize)),
+                format_bytes(step.retained_bytes),
+                change_vs(baseline, size.users, step)
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_run() {
+        let options = BenchOptions {
+            sizes: vec![200],
+            iterations: 2,
+            seed: 1,
+        };
+
+        let report = run(&options, |_| {}).unwrap();
+        let steps: Vec<&str> = report.sizes[0]
+            .steps
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+
+        assert_eq!(report.sizes[0].users, 200);
+        assert_eq!(steps.len(), 11);
+        assert_eq!(steps[0], "ingest");
+        assert!(steps.contains(&"handler/top-countries"));
+        assert_eq!(steps[10], "handler/users-diff");
+        // Os testes não instalam o allocator
+        assert!(report.sizes[0].steps.iter().all(|s| s.peak_bytes.is_none()));
+
+        let table = table(&report, Some(&report));
+        assert!(table.contains("200 users"));
+        assert!(table.contains("+0.0%"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(None), "-");
+        assert_eq!(format_bytes(Some(512)), "512 B");
+        assert_eq!(format_bytes(Some(-2048)), "-2.0 KiB");
+        assert_eq!(format_bytes(Some(3 * 1024 * 1024)), "3.0 MiB");
+    }
+}
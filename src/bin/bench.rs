@@ -0,0 +1,84 @@
+use clap::{Parser, ValueEnum};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use challengeresult::bench::{self, BenchOptions, BenchReport, TrackingAllocator};
+
+// Conta as alocações pra medir o pico de memória de cada passo
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/* Benchmark do ingest e das agregações (veja `src/bench.rs`).
+ * Rode com `--release`, senão os números não dizem nada.
+ */
+#[derive(Parser, Debug)]
+#[command(about = "Benchmarks ingest and analytics over generated datasets")]
+struct Args {
+    /// Dataset sizes (users), comma separated
+    #[arg(long, value_delimiter = ',', default_value = "10000,100000,1000000")]
+    sizes: Vec<usize>,
+
+    /// Runs per step
+    #[arg(short, long, default_value_t = 3)]
+    iterations: usize,
+
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    #[arg(short, long, value_enum, default_value_t = Output::Table)]
+    format: Output,
+
+    /// Previous `--format json` output to compare against
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Output {
+    Table,
+    Json,
+}
+
+fn load_baseline(path: &PathBuf) -> Result<BenchReport, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let options = BenchOptions {
+        sizes: args.sizes,
+        iterations: args.iterations,
+        seed: args.seed,
+    };
+
+    let result = args
+        .baseline
+        .as_ref()
+        .map(load_baseline)
+        .transpose()
+        .and_then(|baseline| {
+            let report = bench::run(&options, |line| eprintln!("{}", line))?;
+            Ok((report, baseline))
+        });
+
+    let (report, baseline) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let output = match args.format {
+        Output::Table => bench::table(&report, baseline.as_ref()),
+        Output::Json => serde_json::to_string_pretty(&report).unwrap() + "\n",
+    };
+
+    let _ = std::io::stdout().write_all(output.as_bytes());
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,81 @@
+use clap::{Parser, ValueEnum};
+use std::io::Write;
+use std::process::ExitCode;
+
+use challengeresult::evaluation::{self, render};
+use challengeresult::scenarios::Scenarios;
+
+/* Avaliador de linha de comando.
+ *
+ * Roda as mesmas checagens do `/evaluation`, só que de fora: não
+ * precisa do servidor chamar a si mesmo, dá pra apontar pra qualquer
+ * instância e usar num shell ou cron.
+ *
+ *   $ cargo run --bin evaluate -- http://127.0.0.1:8000
+ *   $ cargo run --bin evaluate -- localhost:8001 --scenarios samples/scenarios.toml --format json
+ *
+ * Sai com 0 quando tudo passa, 1 quando alguma checagem falha e 2
+ * quando nem deu pra avaliar (URL ou arquivo de cenários inválido).
+ */
+#[derive(Parser, Debug)]
+#[command(about = "Runs the API evaluation checks against a running instance")]
+struct Args {
+    /// Base URL of the instance (ex: http://127.0.0.1:8000 or localhost:8001)
+    #[arg(default_value = "http://127.0.0.1:8000")]
+    target: String,
+
+    /// TOML or JSON scenario file (default: the 4 challenge endpoints)
+    #[arg(short, long)]
+    scenarios: Option<String>,
+
+    #[arg(short, long, value_enum, default_value_t = Output::Table)]
+    format: Output,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Output {
+    Table,
+    Json,
+    Junit,
+    Markdown,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let prepared = evaluation::parse_target(&args.target).and_then(|base_url| {
+        let scenarios = match &args.scenarios {
+            Some(path) => Scenarios::from_file(path)?,
+            None => Scenarios::builtin(),
+        };
+
+        Ok((base_url, scenarios))
+    });
+
+    let (base_url, scenarios) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = evaluation::evaluate_target(&base_url, &scenarios).await;
+
+    let output = match args.format {
+        Output::Table => render::table(&report),
+        Output::Json => serde_json::to_string_pretty(&report).unwrap() + "\n",
+        Output::Junit => render::junit(&report),
+        Output::Markdown => render::markdown(&report),
+    };
+
+    // `| head` fecha o pipe antes da hora; não é motivo pra panic
+    let _ = std::io::stdout().write_all(output.as_bytes());
+
+    if report.success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
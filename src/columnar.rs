@@ -0,0 +1,435 @@
+use chrono::Local;
+use rocket::State;
+use rocket::serde::json::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem::{size_of, size_of_val};
+use std::ops::Range;
+
+use crate::intern::{Symbol, SymbolTable};
+use crate::{Root, User};
+
+/* Representação colunar do dataset, montada uma vez no upload.
+ *
+ * O `Vec<User>` é ótimo pra devolver o usuário inteiro (superusers,
+ * leaderboard), mas pra agregar é um desastre: cada usuário tem um
+ * monte de `String` pequenininha espalhada pela HEAP e, pra contar
+ * país, a gente pula de ponteiro em ponteiro.
+ * Aqui cada campo vira um array (score, idade, ativo, ...), os textos
+ * repetidos (país, time, projeto, ação, data) viram um id denso
+ * (0, 1, 2, ...) num dicionário local e os logs/projetos ficam
+ * 'achatados' com offsets:
+ *
+ *   log_offsets: [0, 4, 7, ...]  => logs do usuário i estão em
+ *   log_dates[log_offsets[i]..log_offsets[i + 1]]
+ */
+#[derive(Default)]
+pub struct Dictionary {
+    values: Vec<Symbol>,
+}
+
+impl Dictionary {
+    pub fn get(&self, id: u32) -> &str {
+        self.values[id as usize].as_str()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    // O texto mora na `SymbolTable` do dataset; aqui é só o ponteiro
+    fn heap_bytes(&self) -> usize {
+        self.values.capacity() * size_of::<Symbol>()
+    }
+}
+
+/* O índice só existe durante o build. Como os campos já chegam
+ * internados, a chave é o `Symbol` e não uma String nova.
+ */
+#[derive(Default)]
+struct DictionaryBuilder {
+    index: HashMap<Symbol, u32>,
+    dictionary: Dictionary,
+}
+
+impl DictionaryBuilder {
+    fn intern(&mut self, value: &Symbol) -> u32 {
+        if let Some(id) = self.index.get(value) {
+            return *id;
+        }
+
+        let id = self.dictionary.values.len() as u32;
+        self.dictionary.values.push(value.clone());
+        self.index.insert(value.clone(), id);
+        id
+    }
+
+    fn finish(self) -> Dictionary {
+        let mut dictionary = self.dictionary;
+        dictionary.values.shrink_to_fit();
+        dictionary
+    }
+}
+
+#[derive(Default)]
+pub struct ColumnarUsers {
+    pub scores: Vec<u16>,
+    pub ages: Vec<u8>,
+    pub active: Vec<bool>,
+    pub leaders: Vec<bool>,
+
+    pub countries: Vec<u32>,
+    pub country_dict: Dictionary,
+    // Código ISO de cada entrada do `country_dict`
+    pub country_codes: Vec<Option<String>>,
+
+    pub teams: Vec<u32>,
+    pub team_dict: Dictionary,
+
+    pub project_offsets: Vec<u32>,
+    pub project_names: Vec<u32>,
+    pub project_completed: Vec<bool>,
+    pub project_dict: Dictionary,
+
+    pub log_offsets: Vec<u32>,
+    pub log_dates: Vec<u32>,
+    pub log_actions: Vec<u32>,
+    pub date_dict: Dictionary,
+    pub action_dict: Dictionary,
+}
+
+/* Parcial de um time durante a agregação: tudo em id/contador,
+ * sem tocar em String. Vira `TeamInsight` só no final.
+ */
+#[derive(Clone, Default)]
+pub struct TeamStats {
+    pub total_members: usize,
+    pub active_count: usize,
+    pub leaders: usize,
+    pub completed_projects: HashSet<u32>,
+}
+
+impl TeamStats {
+    pub fn merge(mut self, other: TeamStats) -> TeamStats {
+        self.total_members += other.total_members;
+        self.active_count += other.active_count;
+        self.leaders += other.leaders;
+        self.completed_projects.extend(other.completed_projects);
+        self
+    }
+}
+
+// Soma posição a posição (os dois vetores usam os mesmos ids)
+pub fn merge_counts(mut a: Vec<usize>, b: Vec<usize>) -> Vec<usize> {
+    for (total, partial) in a.iter_mut().zip(b) {
+        *total += partial;
+    }
+    a
+}
+
+pub fn merge_team_stats(a: Vec<TeamStats>, b: Vec<TeamStats>) -> Vec<TeamStats> {
+    a.into_iter().zip(b).map(|(a, b)| a.merge(b)).collect()
+}
+
+impl ColumnarUsers {
+    pub fn from_users(users: &[User], symbols: &mut SymbolTable) -> ColumnarUsers {
+        let n = users.len();
+
+        let mut columns = ColumnarUsers {
+            scores: Vec::with_capacity(n),
+            ages: Vec::with_capacity(n),
+            active: Vec::with_capacity(n),
+            leaders: Vec::with_capacity(n),
+            countries: Vec::with_capacity(n),
+            teams: Vec::with_capacity(n),
+            project_offsets: Vec::with_capacity(n + 1),
+            log_offsets: Vec::with_capacity(n + 1),
+            ..Default::default()
+        };
+
+        let mut countries = DictionaryBuilder::default();
+        // "Brasil" e "brazil" são Symbols diferentes, mas o mesmo país
+        let mut country_ids: HashMap<&Symbol, u32> = HashMap::new();
+        let mut teams = DictionaryBuilder::default();
+        let mut projects = DictionaryBuilder::default();
+        let mut dates = DictionaryBuilder::default();
+        let mut actions = DictionaryBuilder::default();
+
+        columns.project_offsets.push(0);
+        columns.log_offsets.push(0);
+
+        for u in users {
+            columns.scores.push(u.score);
+            columns.ages.push(u.age);
+            columns.active.push(u.active);
+            columns.leaders.push(u.team.leader);
+
+            let country_id = *country_ids
+                .entry(&u.country)
+                .or_insert_with(|| countries.intern(&symbols.intern(u.country_name())));
+            if country_id as usize == columns.country_codes.len() {
+                columns.country_codes.push(u.country_code.clone());
+            }
+            columns.countries.push(country_id);
+
+            columns.teams.push(teams.intern(&u.team.name));
+
+            for p in u.team.projects.iter() {
+                columns.project_names.push(projects.intern(&p.name));
+                columns.project_completed.push(p.completed);
+            }
+            columns
+                .project_offsets
+                .push(columns.project_names.len() as u32);
+
+            for l in u.logs.iter() {
+                columns.log_dates.push(dates.intern(&l.date));
+                columns.log_actions.push(actions.intern(&l.action));
+            }
+            columns.log_offsets.push(columns.log_dates.len() as u32);
+        }
+
+        columns.project_names.shrink_to_fit();
+        columns.project_completed.shrink_to_fit();
+        columns.log_dates.shrink_to_fit();
+        columns.log_actions.shrink_to_fit();
+
+        columns.country_dict = countries.finish();
+        columns.team_dict = teams.finish();
+        columns.project_dict = projects.finish();
+        columns.date_dict = dates.finish();
+        columns.action_dict = actions.finish();
+
+        columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /* As agregações recebem um intervalo de usuários e devolvem um
+     * parcial que dá pra juntar com outro (`merge_counts`,
+     * `TeamStats::merge`). Assim o `Parallelism` consegue quebrar o
+     * dataset em pedaços e somar no final.
+     */
+
+    // Total de usuários por id de país (índice = id no `country_dict`)
+    pub fn country_counts(&self, users: Range<usize>) -> Vec<usize> {
+        let mut counts = vec![0; self.country_dict.len()];
+
+        for c in self.countries[users].iter() {
+            counts[*c as usize] += 1;
+        }
+
+        counts
+    }
+
+    // Estatísticas por id de time (índice = id no `team_dict`)
+    pub fn team_stats(&self, users: Range<usize>) -> Vec<TeamStats> {
+        let mut stats = vec![TeamStats::default(); self.team_dict.len()];
+
+        for i in users {
+            let team = &mut stats[self.teams[i] as usize];
+
+            team.total_members += 1;
+
+            if self.active[i] {
+                team.active_count += 1;
+            }
+
+            if self.leaders[i] {
+                team.leaders += 1;
+            }
+
+            let projects = self.project_offsets[i] as usize..self.project_offsets[i + 1] as usize;
+            for p in projects {
+                if self.project_completed[p] {
+                    team.completed_projects.insert(self.project_names[p]);
+                }
+            }
+        }
+
+        stats
+    }
+
+    // Quantidade de logs por id de data (índice = id no `date_dict`)
+    pub fn logins_per_day(&self, users: Range<usize>) -> Vec<usize> {
+        let mut counts = vec![0; self.date_dict.len()];
+
+        let logs = self.log_offsets[users.start] as usize..self.log_offsets[users.end] as usize;
+        for d in self.log_dates[logs].iter() {
+            counts[*d as usize] += 1;
+        }
+
+        counts
+    }
+
+    fn heap_bytes(&self) -> BTreeMap<String, usize> {
+        fn vec_bytes<T>(v: &Vec<T>) -> usize {
+            v.capacity() * size_of::<T>()
+        }
+
+        let country_codes = vec_bytes(&self.country_codes)
+            + self
+                .country_codes
+                .iter()
+                .flatten()
+                .map(String::capacity)
+                .sum::<usize>();
+
+        BTreeMap::from([
+            (String::from("scores"), vec_bytes(&self.scores)),
+            (String::from("ages"), vec_bytes(&self.ages)),
+            (String::from("active"), vec_bytes(&self.active)),
+            (String::from("leaders"), vec_bytes(&self.leaders)),
+            (
+                String::from("countries"),
+                vec_bytes(&self.countries) + self.country_dict.heap_bytes() + country_codes,
+            ),
+            (
+                String::from("teams"),
+                vec_bytes(&self.teams) + self.team_dict.heap_bytes(),
+            ),
+            (
+                String::from("projects"),
+                vec_bytes(&self.project_offsets)
+                    + vec_bytes(&self.project_names)
+                    + vec_bytes(&self.project_completed)
+                    + self.project_dict.heap_bytes(),
+            ),
+            (
+                String::from("logs"),
+                vec_bytes(&self.log_offsets)
+                    + vec_bytes(&self.log_dates)
+                    + vec_bytes(&self.log_actions)
+                    + self.date_dict.heap_bytes()
+                    + self.action_dict.heap_bytes(),
+            ),
+        ])
+    }
+}
+
+/* Estimativa do que o `Vec<User>` ocupa: o tamanho das structs mais
+ * tudo o que elas apontam na HEAP (capacity das Strings e dos Vecs).
+ * Os campos internados (`Symbol`) são só um ponteiro - o texto deles
+ * entra na representação "interned", que é compartilhada pelas duas.
+ * Não conta o overhead do allocator, então é um 'piso'.
+ */
+fn row_heap_bytes(users: &[User]) -> BTreeMap<String, usize> {
+    let mut strings = 0;
+    let mut projects = 0;
+    let mut logs = 0;
+
+    for u in users {
+        strings += u.id.capacity()
+            + u.name.capacity()
+            + u.country_code.as_ref().map(String::capacity).unwrap_or(0);
+
+        projects += u.team.projects.capacity() * size_of::<crate::TeamProject>();
+
+        logs += u.logs.capacity() * size_of::<crate::UserLog>();
+    }
+
+    BTreeMap::from([
+        (String::from("users"), size_of_val(users)),
+        (String::from("strings"), strings),
+        (String::from("projects"), projects),
+        (String::from("logs"), logs),
+    ])
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepresentationMemory {
+    name: String,
+    total_bytes: usize,
+    breakdown: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MemoryResp {
+    timestamp: String,
+    version: u64,
+    user_count: usize,
+    representations: Vec<RepresentationMemory>,
+}
+
+fn representation(name: &str, breakdown: BTreeMap<String, usize>) -> RepresentationMemory {
+    RepresentationMemory {
+        name: String::from(name),
+        total_bytes: breakdown.values().sum(),
+        breakdown,
+    }
+}
+
+#[get("/memory")]
+pub fn get_memory(root: &State<Root>) -> Json<MemoryResp> {
+    // Quanto cada representação do dataset atual ocupa (em bytes)
+    let dataset = root.snapshot();
+
+    Json(MemoryResp {
+        timestamp: format!("{:?}", Local::now()),
+        version: dataset.version,
+        user_count: dataset.users.len(),
+        representations: vec![
+            representation("rows", row_heap_bytes(&dataset.users)),
+            representation("columnar", dataset.columns.heap_bytes()),
+            representation(
+                "interned",
+                BTreeMap::from([(String::from("symbol_table"), dataset.symbols.table_bytes())]),
+            ),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{_build_app_with_fixture, _load_fixture_users, _use_root_state};
+
+    #[test]
+    fn test_from_users() {
+        let users = _load_fixture_users("usuarios_10").unwrap();
+        let columns = ColumnarUsers::from_users(&users, &mut SymbolTable::default());
+
+        assert_eq!(columns.len(), 10);
+        assert_eq!(columns.scores[9], 1040);
+        assert_eq!(columns.team_dict.get(columns.teams[9]), "Frontend Avengers");
+        assert_eq!(columns.country_dict.get(columns.countries[9]), "Argentina");
+        assert_eq!(columns.team_dict.len(), 3);
+
+        // Os logs do último usuário são os 5 últimos do array achatado
+        let logs = columns.log_offsets[9] as usize..columns.log_offsets[10] as usize;
+        assert_eq!(logs.len(), 5);
+        assert_eq!(columns.log_dates.len(), columns.log_offsets[10] as usize);
+        assert_eq!(
+            columns.date_dict.get(columns.log_dates[logs.start]),
+            "2025-03-28"
+        );
+        assert_eq!(
+            columns.action_dict.get(columns.log_actions[logs.start]),
+            "login"
+        );
+    }
+
+    #[test]
+    fn test_get_memory() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let resp = get_memory(state).0;
+
+        assert_eq!(resp.user_count, 10);
+        assert_eq!(
+            resp.representations
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rows", "columnar", "interned"]
+        );
+        assert!(
+            resp.representations
+                .iter()
+                .all(|r| r.total_bytes == r.breakdown.values().sum::<usize>())
+        );
+        assert!(resp.representations[1].total_bytes < resp.representations[0].total_bytes);
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/* Configurações da aplicação que moram no `Rocket.toml` (ou nas
+ * variáveis `ROCKET_*`). O Rocket já usa o figment pra ler o arquivo,
+ * então é só pedir pro `AdHoc::config` extrair esta struct - o que não
+ * for nosso (address, limits, ...) é ignorado pelo serde.
+ */
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct AppConfig {
+    pub data_quality: DataQualityConfig,
+    pub countries: CountriesConfig,
+    pub regions: RegionsConfig,
+    pub cache: CacheConfig,
+    pub dataset: DatasetConfig,
+    pub parallel: ParallelConfig,
+    pub load_test: LoadTestConfig,
+    pub evaluation: EvaluationConfig,
+}
+
+impl AppConfig {
+    // Pros subcomandos que rodam sem subir o Rocket (report, bench)
+    pub fn from_rocket_figment() -> Result<Self, String> {
+        rocket::Config::figment()
+            .extract()
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct EvaluationConfig {
+    // TOML/JSON com os cenários; sem ele vale a lista embutida
+    pub scenarios_file: Option<String>,
+    // Quantas execuções o `/evaluation/history` guarda
+    pub history_size: usize,
+    // NDJSON com o histórico, pra sobreviver a um restart
+    pub history_file: Option<String>,
+    // Latência X% pior que a execução anterior = regressão...
+    pub regression_threshold_pct: f64,
+    // ...desde que piore pelo menos isso (ruído de µs não conta)
+    pub regression_min_delta_us: u64,
+    // Hosts que o `?target=` (do `/evaluation` e do `/evaluation/load`)
+    // pode usar além do loopback/localhost
+    pub allowed_targets: Vec<String>,
+}
+
+impl Default for EvaluationConfig {
+    fn default() -> Self {
+        EvaluationConfig {
+            scenarios_file: None,
+            history_size: 100,
+            history_file: None,
+            regression_threshold_pct: 20.0,
+            regression_min_delta_us: 1000,
+            allowed_targets: Vec::new(),
+        }
+    }
+}
+
+// Valores padrão do `/evaluation/load` (a query string sobrescreve)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct LoadTestConfig {
+    pub concurrency: usize,
+    pub duration_secs: u64,
+    // Requests por endpoint descartados antes de medir
+    pub warmup_requests: usize,
+    // Teto pro `?concurrency=`, pra ninguém derrubar o servidor sem querer
+    pub max_concurrency: usize,
+    // Mesma ideia pro `?duration=`, `?requests=` e `?warmup=`
+    pub max_duration_secs: u64,
+    pub max_requests: usize,
+    pub max_warmup_requests: usize,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        LoadTestConfig {
+            concurrency: 8,
+            duration_secs: 10,
+            warmup_requests: 20,
+            max_concurrency: 256,
+            max_duration_secs: 60,
+            max_requests: 100_000,
+            max_warmup_requests: 1_000,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct ParallelConfig {
+    // Abaixo disso a agregação roda numa thread só
+    pub min_users: usize,
+    // Threads do pool de agregação (0 = uma por core)
+    pub threads: usize,
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        ParallelConfig {
+            min_users: 50_000,
+            threads: 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CacheConfig {
+    // true: calcula os relatórios logo após o upload; false: no primeiro GET
+    pub eager: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct DatasetConfig {
+    // Quantas versões (contando a atual) ficam em memória pro
+    // `/users/diff?from=&to=`. Menos que 2 vira 2.
+    pub versions: usize,
+}
+
+impl Default for DatasetConfig {
+    fn default() -> Self {
+        DatasetConfig { versions: 3 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CountriesConfig {
+    // JSON com aliases extras: { "Terra Brasilis": "BR" }
+    pub alias_file: Option<String>,
+}
+
+// Nome da região => países (código ISO) e/ou continentes
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct RegionsConfig {
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct DataQualityConfig {
+    pub min_age: u8,
+    pub max_age: u8,
+    pub min_score: u16,
+    pub max_score: u16,
+    // Quantos ids de exemplo devolver por anomalia
+    pub sample_size: usize,
+}
+
+impl Default for DataQualityConfig {
+    fn default() -> Self {
+        DataQualityConfig {
+            min_age: 14,
+            max_age: 100,
+            min_score: 0,
+            max_score: 1100,
+            sample_size: 5,
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::{ActiveUsersResp, GetSuperusersResp, TeamInsightsResp, TopCountriesResp, User};
+
+/* Gabarito da avaliação.
+ *
+ * Recalcula os resultados esperados direto das linhas (`Vec<User>`),
+ * do jeito mais burro possível: sem a representação colunar, sem o
+ * cache de agregados e sem o map/reduce. Se algum deles quebrar, a
+ * conta daqui não quebra junto.
+ */
+pub struct Reference {
+    superuser_count: usize,
+    // id => campos conferidos nas linhas que o endpoint devolveu
+    superusers: BTreeMap<String, SuperuserReference>,
+    top_countries: Vec<(String, usize)>,
+    teams: BTreeMap<String, TeamReference>,
+    logins: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct SuperuserReference {
+    name: String,
+    age: u8,
+    score: u16,
+    active: bool,
+    country: String,
+    team: String,
+}
+
+impl SuperuserReference {
+    fn from_user(u: &User) -> Self {
+        SuperuserReference {
+            name: u.name.clone(),
+            age: u.age,
+            score: u.score,
+            active: u.active,
+            country: u.country.to_string(),
+            team: u.team.name.to_string(),
+        }
+    }
+}
+
+// Os primeiros `MAX_LISTED_IDS` ids de uma lista e o tamanho dela
+#[derive(Serialize, Default, Debug, PartialEq)]
+struct IdSample {
+    total: usize,
+    ids: Vec<String>,
+}
+
+#[derive(Serialize, Default, Debug, PartialEq)]
+struct TeamReference {
+    total_members: usize,
+    leaders: usize,
+    completed_projects: usize,
+    active_percentage: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    field: String,
+    expected: serde_json::Value,
+    actual: serde_json::Value,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+
+// O endpoint trunca em 1 casa decimal; aqui a conta é em f64 sem truncar
+const PERCENTAGE_TOLERANCE: f64 = 0.1;
+
+// Quantas linhas do `/superusers` têm os campos conferidos um a um
+const SUPERUSER_SPOT_CHECKS: usize = 20;
+
+// Quantos ids sobrando/faltando aparecem no mismatch (o resto só conta)
+const MAX_LISTED_IDS: usize = 10;
+
+impl Reference {
+    pub fn from_users(users: &[User]) -> Self {
+        // Regra do desafio: score >= 900 e active = true
+        let superuser_rows: Vec<&User> = users
+            .iter()
+            .filter(|u| u.score >= 900 && u.active)
+            .collect();
+        let superuser_count = superuser_rows.len();
+        let superusers = superuser_rows
+            .into_iter()
+            .map(|u| (u.id.clone(), SuperuserReference::from_user(u)))
+            .collect();
+
+        let mut countries: HashMap<&str, usize> = HashMap::new();
+        for u in users {
+            *countries.entry(u.country_name()).or_default() += 1;
+        }
+
+        let mut top_countries: Vec<(String, usize)> = countries
+            .into_iter()
+            .map(|(country, total)| (country.to_owned(), total))
+            .collect();
+        top_countries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_countries.truncate(5);
+
+        let mut members: BTreeMap<&str, Vec<&User>> = BTreeMap::new();
+        for u in users {
+            members.entry(u.team.name.as_str()).or_default().push(u);
+        }
+
+        let teams = members
+            .into_iter()
+            .map(|(team, members)| {
+                let active = members.iter().filter(|u| u.active).count();
+                let completed: HashSet<&str> = members
+                    .iter()
+                    .flat_map(|u| u.team.projects.iter())
+                    .filter(|p| p.completed)
+                    .map(|p| p.name.as_str())
+                    .collect();
+
+                let reference = TeamReference {
+                    total_members: members.len(),
+                    leaders: members.iter().filter(|u| u.team.leader).count(),
+                    completed_projects: completed.len(),
+                    active_percentage: active as f64 / members.len() as f64 * 100.0,
+                };
+
+                (team.to_owned(), reference)
+            })
+            .collect();
+
+        // Todo log conta como atividade no dia (é o que o endpoint faz)
+        let mut logins: BTreeMap<String, usize> = BTreeMap::new();
+        for log in users.iter().flat_map(|u| u.logs.iter()) {
+            *logins.entry(log.date.to_string()).or_default() += 1;
+        }
+
+        Reference {
+            superuser_count,
+            superusers,
+            top_countries,
+            teams,
+            logins,
+        }
+    }
+}
+
+fn compare<T: Serialize + PartialEq>(
+    mismatches: &mut Vec<Mismatch>,
+    field: String,
+    expected: T,
+    actual: T,
+) {
+    if expected != actual {
+        mismatches.push(Mismatch {
+            field,
+            expected: serde_json::json!(expected),
+            actual: serde_json::json!(actual),
+        });
+    }
+}
+
+/* Cada resposta sabe se comparar com o gabarito. Lista vazia = bateu. */
+pub trait Verify {
+    fn verify(&self, reference: &Reference) -> Vec<Mismatch>;
+}
+
+impl Verify for GetSuperusersResp {
+    fn verify(&self, reference: &Reference) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+
+        compare(
+            &mut mismatches,
+            String::from("user_count"),
+            reference.superuser_count,
+            self.user_count,
+        );
+        compare(
+            &mut mismatches,
+            String::from("data.len()"),
+            reference.superuser_count,
+            self.data.len(),
+        );
+
+        // Mesmo tamanho não quer dizer mesmos usuários
+        let ids: BTreeSet<&str> = self.data.iter().map(|u| u.id.as_str()).collect();
+        let listed = |ids: Vec<&str>| IdSample {
+            total: ids.len(),
+            ids: ids
+                .into_iter()
+                .take(MAX_LISTED_IDS)
+                .map(String::from)
+                .collect(),
+        };
+
+        let missing = reference
+            .superusers
+            .keys()
+            .map(String::as_str)
+            .filter(|id| !ids.contains(id))
+            .collect();
+        let unexpected = ids
+            .iter()
+            .copied()
+            .filter(|id| !reference.superusers.contains_key(*id))
+            .collect();
+
+        compare(
+            &mut mismatches,
+            String::from("data.missing_ids"),
+            IdSample::default(),
+            listed(missing),
+        );
+        compare(
+            &mut mismatches,
+            String::from("data.unexpected_ids"),
+            IdSample::default(),
+            listed(unexpected),
+        );
+
+        // E o mesmo id não quer dizer a mesma linha: confere os campos
+        // das primeiras que bateram
+        let matching = self
+            .data
+            .iter()
+            .filter_map(|u| reference.superusers.get(&u.id).map(|r| (u, r)))
+            .take(SUPERUSER_SPOT_CHECKS);
+
+        for (user, expected) in matching {
+            compare(
+                &mut mismatches,
+                format!("data[{}]", user.id),
+                expected,
+                &SuperuserReference::from_user(user),
+            );
+        }
+
+        mismatches
+    }
+}
+
+impl Verify for TopCountriesResp {
+    fn verify(&self, reference: &Reference) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        let len = reference.top_countries.len().max(self.countries.len());
+
+        for i in 0..len {
+            compare(
+                &mut mismatches,
+                format!("countries[{}]", i),
+                reference.top_countries.get(i).cloned(),
+                self.countries.get(i).map(|c| (c.country.clone(), c.total)),
+            );
+        }
+
+        mismatches
+    }
+}
+
+impl Verify for TeamInsightsResp {
+    fn verify(&self, reference: &Reference) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+
+        let actual: BTreeMap<&str, TeamReference> = self
+            .teams
+            .iter()
+            .map(|t| {
+                let team = TeamReference {
+                    total_members: t.total_members,
+                    leaders: t.leaders,
+                    completed_projects: t.completed_projects,
+                    active_percentage: t.active_percentage as f64,
+                };
+
+                (t.team.as_str(), team)
+            })
+            .collect();
+
+        let names: BTreeSet<&str> = reference
+            .teams
+            .keys()
+            .map(String::as_str)
+            .chain(actual.keys().copied())
+            .collect();
+
+        for name in names {
+            let (Some(expected), Some(found)) = (reference.teams.get(name), actual.get(name))
+            else {
+                compare(
+                    &mut mismatches,
+                    format!("teams[{}]", name),
+                    reference.teams.get(name),
+                    actual.get(name),
+                );
+                continue;
+            };
+
+            let field = |f: &str| format!("teams[{}].{}", name, f);
+
+            compare(
+                &mut mismatches,
+                field("total_members"),
+                expected.total_members,
+                found.total_members,
+            );
+            compare(
+                &mut mismatches,
+                field("leaders"),
+                expected.leaders,
+                found.leaders,
+            );
+            compare(
+                &mut mismatches,
+                field("completed_projects"),
+                expected.completed_projects,
+                found.completed_projects,
+            );
+
+            if (expected.active_percentage - found.active_percentage).abs() > PERCENTAGE_TOLERANCE {
+                compare(
+                    &mut mismatches,
+                    field("active_percentage"),
+                    expected.active_percentage,
+                    found.active_percentage,
+                );
+            }
+        }
+
+        mismatches
+    }
+}
+
+impl Verify for ActiveUsersResp {
+    fn verify(&self, reference: &Reference) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+
+        let actual: BTreeMap<&str, usize> = self
+            .logins
+            .iter()
+            .map(|l| (l.date.as_str(), l.total))
+            .collect();
+
+        let dates: BTreeSet<&str> = reference
+            .logins
+            .keys()
+            .map(String::as_str)
+            .chain(actual.keys().copied())
+            .collect();
+
+        for date in dates {
+            compare(
+                &mut mismatches,
+                format!("logins[{}]", date),
+                reference.logins.get(date),
+                actual.get(date),
+            );
+        }
+
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::State;
+
+    use crate::negotiation::ResponseFormat::Json;
+    use crate::tests::{_build_app_with_fixture, _use_root_state};
+
+    #[test]
+    fn test_verify_against_reference() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let root = _use_root_state(&rocket);
+        let parallelism = State::get(&rocket).unwrap();
+
+        let reference = Reference::from_users(&root.snapshot().users);
+
+        let mut superusers = crate::get_superusers(Json, root).right().unwrap().0;
+        let countries =
+            crate::get_topcountries(None, Json, root, State::get(&rocket).unwrap(), parallelism).0;
+        let mut teams = crate::get_team_insights(Json, root, parallelism).0;
+        let mut logins = crate::get_active_users_per_day(None, Json, root, parallelism).0;
+
+        assert_eq!(superusers.verify(&reference), vec![]);
+        assert_eq!(countries.verify(&reference), vec![]);
+        assert_eq!(teams.verify(&reference), vec![]);
+        assert_eq!(logins.verify(&reference), vec![]);
+
+        teams.teams[0].leaders += 1;
+        logins.logins.pop();
+
+        // Troca o superusuário por outro usuário qualquer: o tamanho bate,
+        // mas os ids não
+        let superuser =
+            std::mem::replace(&mut superusers.data[0], root.snapshot().users[0].clone());
+
+        let fields: Vec<String> = superusers
+            .verify(&reference)
+            .into_iter()
+            .map(|m| m.field)
+            .collect();
+        assert_eq!(fields, vec!["data.missing_ids", "data.unexpected_ids"]);
+
+        // Mesmo id, score errado
+        superusers.data[0] = superuser;
+        superusers.data[0].score -= 1;
+
+        let mismatches = superusers.verify(&reference);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].field,
+            format!("data[{}]", superusers.data[0].id)
+        );
+
+        let team_mismatches = teams.verify(&reference);
+        assert_eq!(team_mismatches.len(), 1);
+        assert_eq!(
+            team_mismatches[0].field,
+            format!("teams[{}].leaders", teams.teams[0].team)
+        );
+
+        let login_mismatches = logins.verify(&reference);
+        assert_eq!(login_mismatches.len(), 1);
+        assert_eq!(login_mismatches[0].actual, serde_json::Value::Null);
+    }
+}
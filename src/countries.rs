@@ -0,0 +1,360 @@
+use rocket::fairing::AdHoc;
+use rocket::{Build, Rocket};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::User;
+use crate::config::AppConfig;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Continent {
+    Africa,
+    NorthAmerica,
+    SouthAmerica,
+    Asia,
+    Europe,
+    Oceania,
+}
+
+impl Continent {
+    pub const ALL: [Continent; 6] = [
+        Continent::Africa,
+        Continent::NorthAmerica,
+        Continent::SouthAmerica,
+        Continent::Asia,
+        Continent::Europe,
+        Continent::Oceania,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Continent::Africa => "África",
+            Continent::NorthAmerica => "América do Norte",
+            Continent::SouthAmerica => "América do Sul",
+            Continent::Asia => "Ásia",
+            Continent::Europe => "Europa",
+            Continent::Oceania => "Oceania",
+        }
+    }
+}
+
+pub struct CountryInfo {
+    pub code: &'static str,
+    pub continent: Continent,
+    pub pt: &'static str,
+    pub en: &'static str,
+    pub es: &'static str,
+}
+
+const fn country(
+    code: &'static str,
+    continent: Continent,
+    pt: &'static str,
+    en: &'static str,
+    es: &'static str,
+) -> CountryInfo {
+    CountryInfo {
+        code,
+        continent,
+        pt,
+        en,
+        es,
+    }
+}
+
+/* Tabela ISO 3166-1 alpha-2 com continente e os nomes em pt/en/es.
+ * América Central e Caribe entram em América do Norte.
+ * O nome em português é o 'display name' (o dataset vem em pt).
+ * Não é a lista completa de países - só o que aparece (ou pode
+ * aparecer) nos exports. Faltou algum? Usa o arquivo de aliases.
+ */
+#[rustfmt::skip]
+pub static COUNTRIES: &[CountryInfo] = &[
+    country("AE", Continent::Asia, "Emirados Árabes Unidos", "United Arab Emirates",
+            "Emiratos Árabes Unidos"),
+    country("AO", Continent::Africa, "Angola", "Angola", "Angola"),
+    country("AR", Continent::SouthAmerica, "Argentina", "Argentina", "Argentina"),
+    country("AT", Continent::Europe, "Áustria", "Austria", "Austria"),
+    country("AU", Continent::Oceania, "Austrália", "Australia", "Australia"),
+    country("BD", Continent::Asia, "Bangladesh", "Bangladesh", "Bangladés"),
+    country("BE", Continent::Europe, "Bélgica", "Belgium", "Bélgica"),
+    country("BO", Continent::SouthAmerica, "Bolívia", "Bolivia", "Bolivia"),
+    country("BR", Continent::SouthAmerica, "Brasil", "Brazil", "Brasil"),
+    country("CA", Continent::NorthAmerica, "Canadá", "Canada", "Canadá"),
+    country("CH", Continent::Europe, "Suíça", "Switzerland", "Suiza"),
+    country("CL", Continent::SouthAmerica, "Chile", "Chile", "Chile"),
+    country("CN", Continent::Asia, "China", "China", "China"),
+    country("CO", Continent::SouthAmerica, "Colômbia", "Colombia", "Colombia"),
+    country("CR", Continent::NorthAmerica, "Costa Rica", "Costa Rica", "Costa Rica"),
+    country("CU", Continent::NorthAmerica, "Cuba", "Cuba", "Cuba"),
+    country("CZ", Continent::Europe, "Tchéquia", "Czechia", "Chequia"),
+    country("DE", Continent::Europe, "Alemanha", "Germany", "Alemania"),
+    country("DK", Continent::Europe, "Dinamarca", "Denmark", "Dinamarca"),
+    country("DO", Continent::NorthAmerica, "República Dominicana", "Dominican Republic",
+            "República Dominicana"),
+    country("EC", Continent::SouthAmerica, "Equador", "Ecuador", "Ecuador"),
+    country("EG", Continent::Africa, "Egito", "Egypt", "Egipto"),
+    country("ES", Continent::Europe, "Espanha", "Spain", "España"),
+    country("FI", Continent::Europe, "Finlândia", "Finland", "Finlandia"),
+    country("FR", Continent::Europe, "França", "France", "Francia"),
+    country("GB", Continent::Europe, "Reino Unido", "United Kingdom", "Reino Unido"),
+    country("GR", Continent::Europe, "Grécia", "Greece", "Grecia"),
+    country("GT", Continent::NorthAmerica, "Guatemala", "Guatemala", "Guatemala"),
+    country("HK", Continent::Asia, "Hong Kong", "Hong Kong", "Hong Kong"),
+    country("ID", Continent::Asia, "Indonésia", "Indonesia", "Indonesia"),
+    country("IE", Continent::Europe, "Irlanda", "Ireland", "Irlanda"),
+    country("IL", Continent::Asia, "Israel", "Israel", "Israel"),
+    country("IN", Continent::Asia, "Índia", "India", "India"),
+    country("IT", Continent::Europe, "Itália", "Italy", "Italia"),
+    country("JP", Continent::Asia, "Japão", "Japan", "Japón"),
+    country("KE", Continent::Africa, "Quênia", "Kenya", "Kenia"),
+    country("KR", Continent::Asia, "Coreia do Sul", "South Korea", "Corea del Sur"),
+    country("MA", Continent::Africa, "Marrocos", "Morocco", "Marruecos"),
+    country("MX", Continent::NorthAmerica, "México", "Mexico", "México"),
+    country("MY", Continent::Asia, "Malásia", "Malaysia", "Malasia"),
+    country("MZ", Continent::Africa, "Moçambique", "Mozambique", "Mozambique"),
+    country("NG", Continent::Africa, "Nigéria", "Nigeria", "Nigeria"),
+    country("NL", Continent::Europe, "Países Baixos", "Netherlands", "Países Bajos"),
+    country("NO", Continent::Europe, "Noruega", "Norway", "Noruega"),
+    country("NZ", Continent::Oceania, "Nova Zelândia", "New Zealand", "Nueva Zelanda"),
+    country("PA", Continent::NorthAmerica, "Panamá", "Panama", "Panamá"),
+    country("PE", Continent::SouthAmerica, "Peru", "Peru", "Perú"),
+    country("PH", Continent::Asia, "Filipinas", "Philippines", "Filipinas"),
+    country("PK", Continent::Asia, "Paquistão", "Pakistan", "Pakistán"),
+    country("PL", Continent::Europe, "Polônia", "Poland", "Polonia"),
+    country("PT", Continent::Europe, "Portugal", "Portugal", "Portugal"),
+    country("PY", Continent::SouthAmerica, "Paraguai", "Paraguay", "Paraguay"),
+    country("RU", Continent::Europe, "Rússia", "Russia", "Rusia"),
+    country("SA", Continent::Asia, "Arábia Saudita", "Saudi Arabia", "Arabia Saudita"),
+    country("SE", Continent::Europe, "Suécia", "Sweden", "Suecia"),
+    country("SG", Continent::Asia, "Singapura", "Singapore", "Singapur"),
+    country("TH", Continent::Asia, "Tailândia", "Thailand", "Tailandia"),
+    country("TR", Continent::Asia, "Turquia", "Turkey", "Turquía"),
+    country("TW", Continent::Asia, "Taiwan", "Taiwan", "Taiwán"),
+    country("UA", Continent::Europe, "Ucrânia", "Ukraine", "Ucrania"),
+    country("US", Continent::NorthAmerica, "Estados Unidos", "United States", "Estados Unidos"),
+    country("UY", Continent::SouthAmerica, "Uruguai", "Uruguay", "Uruguay"),
+    country("VE", Continent::SouthAmerica, "Venezuela", "Venezuela", "Venezuela"),
+    country("VN", Continent::Asia, "Vietnã", "Vietnam", "Vietnam"),
+    country("ZA", Continent::Africa, "África do Sul", "South Africa", "Sudáfrica"),
+];
+
+// Apelidos que não são o nome 'oficial' em nenhuma das três línguas
+static EXTRA_ALIASES: &[(&str, &str)] = &[
+    ("EUA", "US"),
+    ("USA", "US"),
+    ("EEUU", "US"),
+    ("Estados Unidos da América", "US"),
+    ("United States of America", "US"),
+    ("UK", "GB"),
+    ("Grã-Bretanha", "GB"),
+    ("Great Britain", "GB"),
+    ("Holanda", "NL"),
+    ("Holland", "NL"),
+    ("República Tcheca", "CZ"),
+    ("Czech Republic", "CZ"),
+    ("República Checa", "CZ"),
+    ("Coreia", "KR"),
+    ("Korea", "KR"),
+    ("Polónia", "PL"),
+    ("Vietname", "VN"),
+];
+
+pub fn find_country(code: &str) -> Option<&'static CountryInfo> {
+    COUNTRIES.iter().find(|c| c.code == code)
+}
+
+pub fn display_name(code: &str) -> Option<&'static str> {
+    find_country(code).map(|c| c.pt)
+}
+
+/* Chave de comparação: minúsculo, sem acento, sem pontuação e com
+ * os espaços normalizados. Assim "Japão", "japao" e "JAPÃO " batem.
+ */
+pub(crate) fn normalize_key(value: &str) -> String {
+    let folded: String = value
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            '-' | '_' => ' ',
+            c => c,
+        })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+
+    folded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct UnmappedCountry {
+    pub country: String,
+    pub total: usize,
+}
+
+pub struct CountryRegistry {
+    aliases: HashMap<String, &'static str>,
+}
+
+impl CountryRegistry {
+    pub fn builtin() -> Self {
+        let mut aliases = HashMap::new();
+
+        for c in COUNTRIES {
+            for name in [c.code, c.pt, c.en, c.es] {
+                aliases.insert(normalize_key(name), c.code);
+            }
+        }
+
+        for (alias, code) in EXTRA_ALIASES {
+            aliases.insert(normalize_key(alias), *code);
+        }
+
+        CountryRegistry { aliases }
+    }
+
+    /* O arquivo de aliases é um JSON simples: { "alias": "CODE" }.
+     * O código precisa existir na tabela (é de lá que sai o display name).
+     */
+    pub fn with_alias_file(mut self, path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let extra: HashMap<String, String> =
+            serde_json::from_str(&content).map_err(|e| format!("{}: {}", path, e))?;
+
+        for (alias, code) in extra {
+            let info = find_country(&code.to_uppercase())
+                .ok_or_else(|| format!("{}: unknown country code '{}'", path, code))?;
+
+            self.aliases.insert(normalize_key(&alias), info.code);
+        }
+
+        Ok(self)
+    }
+
+    pub fn resolve(&self, country: &str) -> Option<&'static str> {
+        self.aliases.get(&normalize_key(country)).copied()
+    }
+
+    /* Preenche o `country_code` de cada usuário. Quem não bater com
+     * nenhum alias fica com `None` e entra no relatório de não mapeados.
+     */
+    pub fn normalize(&self, users: &mut [User]) -> Vec<UnmappedCountry> {
+        let mut unmapped: HashMap<String, usize> = HashMap::new();
+
+        for u in users.iter_mut() {
+            u.country_code = self.resolve(&u.country).map(String::from);
+
+            if u.country_code.is_none() {
+                *unmapped.entry(u.country.to_string()).or_default() += 1;
+            }
+        }
+
+        let mut unmapped: Vec<UnmappedCountry> = unmapped
+            .into_iter()
+            .map(|(country, total)| UnmappedCountry { country, total })
+            .collect();
+
+        unmapped.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.country.cmp(&b.country))
+        });
+
+        unmapped
+    }
+}
+
+pub fn registry_fairing() -> AdHoc {
+    AdHoc::try_on_ignite("Country Registry", |rocket: Rocket<Build>| async move {
+        let alias_file = rocket
+            .state::<AppConfig>()
+            .and_then(|c| c.countries.alias_file.clone());
+
+        let registry = match alias_file {
+            Some(path) => CountryRegistry::builtin().with_alias_file(&path),
+            None => Ok(CountryRegistry::builtin()),
+        };
+
+        match registry {
+            Ok(registry) => Ok(rocket.manage(registry)),
+            Err(e) => {
+                log::error!("failed to load country aliases: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::Symbol;
+    use crate::tests::_load_fixture_users;
+    use std::io::Write;
+
+    #[test]
+    fn test_resolve_country_names() {
+        let registry = CountryRegistry::builtin();
+
+        for name in ["Brasil", "Brazil", "brasil", " BRASIL ", "BR"] {
+            assert_eq!(registry.resolve(name), Some("BR"), "{}", name);
+        }
+
+        assert_eq!(registry.resolve("Japão"), Some("JP"));
+        assert_eq!(registry.resolve("Japon"), Some("JP"));
+        assert_eq!(registry.resolve("EUA"), Some("US"));
+        assert_eq!(registry.resolve("Atlântida"), None);
+    }
+
+    #[test]
+    fn test_normalize_reports_unmapped() {
+        let mut users = _load_fixture_users("usuarios_10").unwrap();
+        users[0].country = Symbol::intern("Atlântida");
+        users[1].country = Symbol::intern("Atlântida");
+        users[2].country = Symbol::intern("brazil");
+
+        let unmapped = CountryRegistry::builtin().normalize(&mut users);
+
+        assert_eq!(
+            unmapped,
+            vec![UnmappedCountry {
+                country: "Atlântida".into(),
+                total: 2
+            }]
+        );
+        assert_eq!(users[0].country_code, None);
+        assert_eq!(users[2].country_code.as_deref(), Some("BR"));
+        assert_eq!(users[9].country_code.as_deref(), Some("AR"));
+    }
+
+    #[test]
+    fn test_with_alias_file() {
+        let path = std::env::temp_dir().join("challengeresult_country_aliases.json");
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(r#"{ "Terra Brasilis": "br" }"#.as_bytes())
+            .unwrap();
+
+        let registry = CountryRegistry::builtin()
+            .with_alias_file(path.to_str().unwrap())
+            .unwrap();
+
+        assert_eq!(registry.resolve("terra brasilis"), Some("BR"));
+
+        file = fs::File::create(&path).unwrap();
+        file.write_all(r#"{ "Atlântida": "XX" }"#.as_bytes())
+            .unwrap();
+
+        assert!(
+            CountryRegistry::builtin()
+                .with_alias_file(path.to_str().unwrap())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use chrono::Local;
+use rocket::State;
+use rocket::response::status::NotFound;
+use rocket::serde::json::Json;
+use rocket::tokio::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::parallel::Parallelism;
+use crate::{CountrySummary, Dataset, Root, TeamInsight, User, is_superuser};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ValueChange<T> {
+    from: T,
+    to: T,
+}
+
+/* O `changed_fields` lista tudo o que mudou. Os campos que o pessoal
+ * mais pergunta (score, active e time) ganham um detalhe a mais.
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct UserChange {
+    id: String,
+    changed_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score_delta: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active: Option<ValueChange<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<ValueChange<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CountDelta {
+    before: usize,
+    after: usize,
+    delta: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CountryDelta {
+    country: String,
+    before: usize,
+    after: usize,
+    delta: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TeamDelta {
+    team: String,
+    total_members: CountDelta,
+    leaders: CountDelta,
+    completed_projects: CountDelta,
+    active_percentage_delta: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AggregateDeltas {
+    user_count: CountDelta,
+    superuser_count: CountDelta,
+    top_countries_before: Vec<CountrySummary>,
+    top_countries_after: Vec<CountrySummary>,
+    countries: Vec<CountryDelta>,
+    teams: Vec<TeamDelta>,
+}
+
+/* Ids que aparecem mais de uma vez numa das versões. Não dá pra saber
+ * qual linha comparar com qual, então esses ficam de fora do
+ * `added`/`removed`/`modified` e aparecem só aqui.
+ */
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct DuplicateIds {
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DatasetDiffResp {
+    timestamp: String,
+    execution_time_ms: u128,
+    from_version: u64,
+    to_version: u64,
+    added: Vec<String>,
+    removed: Vec<String>,
+    modified: Vec<UserChange>,
+    duplicate_ids: DuplicateIds,
+    aggregates: AggregateDeltas,
+}
+
+fn count_delta(before: usize, after: usize) -> CountDelta {
+    CountDelta {
+        before,
+        after,
+        delta: after as i64 - before as i64,
+    }
+}
+
+fn diff_user(old: &User, new: &User) -> Option<UserChange> {
+    let mut changed_fields = Vec::new();
+
+    let fields = [
+        ("name", old.name != new.name),
+        ("age", old.age != new.age),
+        ("score", old.score != new.score),
+        ("active", old.active != new.active),
+        ("country", old.country != new.country),
+        ("team.name", old.team.name != new.team.name),
+        ("team.leader", old.team.leader != new.team.leader),
+        ("team.projects", old.team.projects != new.team.projects),
+        ("logs", old.logs != new.logs),
+    ];
+
+    for (field, changed) in fields {
+        if changed {
+            changed_fields.push(String::from(field));
+        }
+    }
+
+    if changed_fields.is_empty() {
+        return None;
+    }
+
+    Some(UserChange {
+        id: new.id.clone(),
+        changed_fields,
+        score_delta: (old.score != new.score).then(|| new.score as i32 - old.score as i32),
+        active: (old.active != new.active).then_some(ValueChange {
+            from: old.active,
+            to: new.active,
+        }),
+        team: (old.team.name != new.team.name).then(|| ValueChange {
+            from: old.team.name.to_string(),
+            to: new.team.name.to_string(),
+        }),
+    })
+}
+
+// As contagens de cada versão vêm do cache de agregados do `Dataset`
+fn diff_countries(old: &Dataset, new: &Dataset, parallelism: &Parallelism) -> Vec<CountryDelta> {
+    let mut totals: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+
+    for c in old.aggregates(parallelism).countries.iter() {
+        totals.entry(c.country.clone()).or_default().0 = c.total;
+    }
+
+    for c in new.aggregates(parallelism).countries.iter() {
+        totals.entry(c.country.clone()).or_default().1 = c.total;
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(country, (before, after))| CountryDelta {
+            country,
+            before,
+            after,
+            delta: after as i64 - before as i64,
+        })
+        .collect()
+}
+
+fn diff_teams(old: &Dataset, new: &Dataset, parallelism: &Parallelism) -> Vec<TeamDelta> {
+    let mut insights: BTreeMap<String, (Option<&TeamInsight>, Option<&TeamInsight>)> =
+        BTreeMap::new();
+
+    for t in old.aggregates(parallelism).teams.iter() {
+        insights.entry(t.team.clone()).or_default().0 = Some(t);
+    }
+
+    for t in new.aggregates(parallelism).teams.iter() {
+        insights.entry(t.team.clone()).or_default().1 = Some(t);
+    }
+
+    insights
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(team, (before, after))| {
+            let before = before.cloned().unwrap_or_else(TeamInsight::new);
+            let after = after.cloned().unwrap_or_else(TeamInsight::new);
+
+            TeamDelta {
+                team,
+                total_members: count_delta(before.total_members, after.total_members),
+                leaders: count_delta(before.leaders, after.leaders),
+                completed_projects: count_delta(
+                    before.completed_projects,
+                    after.completed_projects,
+                ),
+                active_percentage_delta: after.active_percentage - before.active_percentage,
+            }
+        })
+        .collect()
+}
+
+// id => usuário, mais os ids que apareceram repetidos
+fn index_by_id(users: &[User]) -> (HashMap<&str, &User>, BTreeSet<&str>) {
+    let mut by_id = HashMap::with_capacity(users.len());
+    let mut duplicates = BTreeSet::new();
+
+    for u in users.iter() {
+        if by_id.insert(u.id.as_str(), u).is_some() {
+            duplicates.insert(u.id.as_str());
+        }
+    }
+
+    (by_id, duplicates)
+}
+
+fn diff_datasets(old: &Dataset, new: &Dataset, parallelism: &Parallelism) -> DatasetDiffResp {
+    let start_time = Instant::now();
+
+    let (mut old_by_id, old_duplicates) = index_by_id(&old.users);
+    let (mut new_by_id, new_duplicates) = index_by_id(&new.users);
+
+    for id in old_duplicates.iter().chain(new_duplicates.iter()) {
+        old_by_id.remove(id);
+        new_by_id.remove(id);
+    }
+
+    // BTreeSet pra lista sair ordenada pelo id (resposta determinística)
+    let added: BTreeSet<&str> = new_by_id
+        .keys()
+        .filter(|id| !old_by_id.contains_key(*id))
+        .copied()
+        .collect();
+
+    let removed: BTreeSet<&str> = old_by_id
+        .keys()
+        .filter(|id| !new_by_id.contains_key(*id))
+        .copied()
+        .collect();
+
+    let mut modified: Vec<UserChange> = new_by_id
+        .iter()
+        .filter_map(|(id, new_user)| {
+            old_by_id
+                .get(id)
+                .and_then(|old_user| diff_user(old_user, new_user))
+        })
+        .collect();
+
+    modified.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let superusers = |users: &[User]| users.iter().filter(|u| is_superuser(u)).count();
+
+    let aggregates = AggregateDeltas {
+        user_count: count_delta(old.users.len(), new.users.len()),
+        superuser_count: count_delta(superusers(&old.users), superusers(&new.users)),
+        top_countries_before: old.top_countries(parallelism),
+        top_countries_after: new.top_countries(parallelism),
+        countries: diff_countries(old, new, parallelism),
+        teams: diff_teams(old, new, parallelism),
+    };
+
+    DatasetDiffResp {
+        timestamp: format!("{:?}", Local::now()),
+        execution_time_ms: start_time.elapsed().as_millis(),
+        from_version: old.version,
+        to_version: new.version,
+        added: added.into_iter().map(String::from).collect(),
+        removed: removed.into_iter().map(String::from).collect(),
+        modified,
+        duplicate_ids: DuplicateIds {
+            from: old_duplicates.into_iter().map(String::from).collect(),
+            to: new_duplicates.into_iter().map(String::from).collect(),
+        },
+        aggregates,
+    }
+}
+
+#[get("/users/diff?<from>&<to>")]
+pub fn get_users_diff(
+    from: Option<u64>,
+    to: Option<u64>,
+    root: &State<Root>,
+    parallelism: &State<Parallelism>,
+) -> Result<Json<DatasetDiffResp>, NotFound<String>> {
+    // Compara duas versões do dataset (por `User.id`). Sem parâmetros é
+    // a anterior contra a atual; `?from=3&to=5` escolhe as versões, desde
+    // que ainda estejam em memória (`[default.dataset] versions`).
+    // Antes do primeiro upload não tem o que comparar => 404.
+    let to = match to {
+        Some(to) => root.version(to),
+        None => Some(root.snapshot()),
+    };
+    let from = match (from, &to) {
+        (Some(from), _) => root.version(from),
+        (None, Some(to)) => to.version.checked_sub(1).and_then(|v| root.version(v)),
+        (None, None) => None,
+    };
+
+    match (from, to) {
+        (Some(from), Some(to)) => Ok(Json(diff_datasets(&from, &to, parallelism))),
+        _ => Err(NotFound(format!(
+            "version not available; retained versions: {:?}",
+            root.retained_versions()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::Symbol;
+    use crate::tests::{_build_app_with_empty_root, _load_fixture_users, _use_root_state};
+
+    #[test]
+    fn test_get_users_diff() {
+        let rocket = _build_app_with_empty_root();
+        let root = _use_root_state(&rocket);
+
+        assert!(get_users_diff(None, None, root, State::get(&rocket).unwrap()).is_err());
+
+        let old_users = _load_fixture_users("usuarios_10").unwrap();
+        let mut new_users = old_users.clone();
+
+        // Ana Sophia sai, Clarice perde pontos e Sarah muda de time
+        let removed = new_users.remove(0);
+        new_users[8].score = 800;
+        new_users[2].team.name = Symbol::intern("UX Wizards");
+        new_users[2].active = false;
+
+        let mut added = new_users[0].clone();
+        added.id = String::from("new-user");
+        added.country = Symbol::intern("Brasil");
+        new_users.push(added);
+
+        root.update(old_users);
+        root.update(new_users);
+
+        let resp = get_users_diff(None, None, root, State::get(&rocket).unwrap())
+            .unwrap()
+            .0;
+
+        assert_eq!((resp.from_version, resp.to_version), (1, 2));
+        assert_eq!(resp.added, vec!["new-user"]);
+        assert_eq!(resp.removed, vec![removed.id]);
+        assert_eq!(
+            resp.modified,
+            vec![
+                UserChange {
+                    id: "32ccb15e-acfa-48c4-b9df-6f416864c737".into(),
+                    changed_fields: vec!["active".into(), "team.name".into()],
+                    score_delta: None,
+                    active: Some(ValueChange {
+                        from: true,
+                        to: false
+                    }),
+                    team: Some(ValueChange {
+                        from: "Frontend Avengers".into(),
+                        to: "UX Wizards".into()
+                    }),
+                },
+                UserChange {
+                    id: "c460b871-77ec-46f1-9127-22ea6989b0bc".into(),
+                    changed_fields: vec!["score".into()],
+                    score_delta: Some(-240),
+                    active: None,
+                    team: None,
+                },
+            ]
+        );
+
+        assert_eq!(resp.aggregates.superuser_count, count_delta(1, 0));
+        assert_eq!(
+            resp.aggregates.countries,
+            vec![
+                CountryDelta {
+                    country: "Brasil".into(),
+                    before: 1,
+                    after: 2,
+                    delta: 1
+                },
+                CountryDelta {
+                    country: "Índia".into(),
+                    before: 1,
+                    after: 0,
+                    delta: -1
+                },
+            ]
+        );
+        assert_eq!(
+            resp.aggregates
+                .teams
+                .iter()
+                .map(|t| (t.team.as_str(), t.total_members.delta))
+                .collect::<Vec<_>>(),
+            vec![("Frontend Avengers", -2), ("UX Wizards", 2)]
+        );
+    }
+
+    #[test]
+    fn test_users_diff_versions() {
+        let rocket = _build_app_with_empty_root();
+        let root = _use_root_state(&rocket);
+        let parallelism = State::get(&rocket).unwrap();
+
+        let users = _load_fixture_users("usuarios_10").unwrap();
+
+        // v1..v4: cada versão perde o primeiro usuário da anterior
+        for version in 0..4 {
+            root.update(users[version..].to_vec());
+        }
+
+        let resp = get_users_diff(Some(2), Some(4), root, parallelism)
+            .unwrap()
+            .0;
+        assert_eq!((resp.from_version, resp.to_version), (2, 4));
+        assert_eq!(resp.removed.len(), 2);
+
+        // Ao contrário também vale
+        let resp = get_users_diff(Some(4), Some(3), root, parallelism)
+            .unwrap()
+            .0;
+        assert_eq!(resp.added, vec![users[2].id.clone()]);
+
+        // Só as 3 últimas ficam em memória
+        let err = get_users_diff(Some(1), None, root, parallelism).unwrap_err();
+        assert_eq!(err.0, "version not available; retained versions: [2, 3, 4]");
+        assert!(get_users_diff(None, Some(9), root, parallelism).is_err());
+
+        // Id repetido: aparece no `duplicate_ids` e não no resto
+        let mut duplicated = users.clone();
+        duplicated[1].id = duplicated[0].id.clone();
+        duplicated[1].score += 1;
+        root.update(duplicated);
+
+        let resp = get_users_diff(None, None, root, parallelism).unwrap().0;
+        assert_eq!(resp.duplicate_ids.from, Vec::<String>::new());
+        assert_eq!(resp.duplicate_ids.to, vec![users[0].id.clone()]);
+        assert!(!resp.added.contains(&users[0].id));
+        assert!(resp.modified.iter().all(|m| m.id != users[0].id));
+    }
+}
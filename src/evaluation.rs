@@ -0,0 +1,718 @@
+use rocket::State;
+use rocket::response::status::BadRequest;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use crate::columnar::MemoryResp;
+use crate::config::{AppConfig, EvaluationConfig};
+use crate::correctness::{Mismatch, Reference, Verify};
+use crate::diff::DatasetDiffResp;
+use crate::history::EvaluationHistory;
+use crate::latency::{self, Latency, TimedResponse};
+use crate::leaderboard::LeaderboardResp;
+use crate::quality::DataQualityResp;
+use crate::scenarios::{ResponseKind, Scenario, Scenarios};
+use crate::{ActiveUsersResp, GetSuperusersResp, Root, TeamInsightsResp, TopCountriesResp};
+
+pub mod render;
+
+use render::{EvaluationOutput, ReportFormat};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RouteMetric {
+    // None quando nem chegou resposta (conexão recusada, timeout...)
+    status: Option<u16>,
+    // O que o endpoint diz que gastou (`execution_time_ms` do body)
+    #[serde(serialize_with = "crate::negotiation::serialize_millis_opt")]
+    server_time_ms: Option<u128>,
+    // O que o avaliador mediu do lado de fora
+    latency: Option<Latency>,
+    valid_response: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    // Só é preenchido quando dá pra comparar com o dataset local
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    correct: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    mismatches: Vec<Mismatch>,
+    // Só quando o cenário tem `latency_budget_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    within_budget: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EvaluationResp {
+    // Id no `/evaluation/history`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_id: Option<u64>,
+    target: String,
+    passed: usize,
+    failed: usize,
+    // % de endpoints que passaram
+    score: f32,
+    success: bool,
+    tested_endpoints: BTreeMap<String, RouteMetric>,
+    // Execução anterior (mesmo alvo) usada de base pras regressões
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    baseline_run_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    regressions: Vec<Regression>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Regression {
+    // Passava na execução anterior e agora não passa mais
+    Failing {
+        endpoint: String,
+        error: Option<String>,
+    },
+    Latency {
+        endpoint: String,
+        baseline_us: u64,
+        current_us: u64,
+        change_pct: f64,
+    },
+}
+
+impl EvaluationResp {
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    pub fn with_run_id(self, run_id: u64) -> Self {
+        EvaluationResp {
+            run_id: Some(run_id),
+            ..self
+        }
+    }
+}
+
+impl RouteMetric {
+    fn unreachable(error: String) -> Self {
+        RouteMetric {
+            status: None,
+            server_time_ms: None,
+            latency: None,
+            valid_response: false,
+            error: Some(error),
+            correct: None,
+            mismatches: Vec::new(),
+            within_budget: None,
+        }
+    }
+
+    fn passed(&self) -> bool {
+        self.valid_response && self.correct != Some(false) && self.within_budget != Some(false)
+    }
+}
+
+/* Compara cada endpoint com a mesma checagem da execução anterior.
+ * Endpoint novo (sem base) não tem como regredir.
+ */
+fn detect_regressions(
+    baseline: &EvaluationResp,
+    current: &EvaluationResp,
+    config: &EvaluationConfig,
+) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+
+    for (endpoint, metric) in current.tested_endpoints.iter() {
+        let Some(before) = baseline.tested_endpoints.get(endpoint) else {
+            continue;
+        };
+
+        if before.passed() && !metric.passed() {
+            regressions.push(Regression::Failing {
+                endpoint: endpoint.clone(),
+                error: metric.error.clone(),
+            });
+            continue;
+        }
+
+        let (Some(before), Some(after)) = (before.latency, metric.latency) else {
+            continue;
+        };
+
+        let (baseline_us, current_us) = (before.total_us, after.total_us);
+        if baseline_us == 0 || current_us < baseline_us + config.regression_min_delta_us {
+            continue;
+        }
+
+        let change_pct = (current_us - baseline_us) as f64 / baseline_us as f64 * 100.0;
+        if change_pct > config.regression_threshold_pct {
+            regressions.push(Regression::Latency {
+                endpoint: endpoint.clone(),
+                baseline_us,
+                current_us,
+                change_pct,
+            });
+        }
+    }
+
+    regressions
+}
+
+/* A antiga "classe" Evaluation tinha uma função por endpoint, todas
+ * iguais e cheias de unwrap: um endpoint fora do ar derrubava o
+ * handler inteiro. Agora é uma checagem só, guiada pelo cenário
+ * (`scenarios.rs`), e qualquer falha vira um RouteMetric com `error`.
+ */
+async fn check(
+    base_url: &str,
+    scenario: &Scenario,
+    reference: Option<&Reference>,
+    timeout: Duration,
+) -> (String, RouteMetric) {
+    let metric = match latency::timed_get(&scenario.url(base_url), timeout).await {
+        Ok(resp) => check_response(resp, scenario, reference),
+        Err(e) => RouteMetric::unreachable(format!("request failed: {}", e)),
+    };
+
+    (scenario.name(), metric)
+}
+
+fn check_response(
+    resp: TimedResponse,
+    scenario: &Scenario,
+    reference: Option<&Reference>,
+) -> RouteMetric {
+    let mut metric = RouteMetric {
+        status: Some(resp.status),
+        server_time_ms: None,
+        latency: Some(resp.latency),
+        valid_response: false,
+        error: None,
+        correct: None,
+        mismatches: Vec::new(),
+        within_budget: None,
+    };
+
+    if let Some(budget_ms) = scenario.latency_budget_ms {
+        metric.within_budget = Some(resp.latency.total_us <= budget_ms * 1000);
+    }
+
+    if let Err(e) = validate_response(&mut metric, resp, scenario, reference) {
+        metric.error = Some(e);
+    } else if metric.within_budget == Some(false) {
+        metric.error = Some(format!(
+            "over latency budget ({} us > {} ms)",
+            metric.latency.unwrap_or_default().total_us,
+            scenario.latency_budget_ms.unwrap_or_default()
+        ));
+    }
+
+    metric
+}
+
+fn validate_response(
+    metric: &mut RouteMetric,
+    resp: TimedResponse,
+    scenario: &Scenario,
+    reference: Option<&Reference>,
+) -> Result<(), String> {
+    if resp.status != scenario.expected_status {
+        return Err(format!(
+            "unexpected status {} (expected {})",
+            resp.status, scenario.expected_status
+        ));
+    }
+
+    // Cenário de erro (ex: 404) ou `any`: o status já basta
+    if scenario.expected_status != 200 || scenario.response == ResponseKind::Any {
+        metric.valid_response = true;
+        return Ok(());
+    }
+
+    let body: serde_json::Value =
+        serde_json::from_slice(&resp.body).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    metric.server_time_ms = body
+        .get("execution_time_ms")
+        .and_then(serde_json::Value::as_u64)
+        .map(u128::from);
+
+    // JSON válido mas fora do formato esperado também é falha
+    let mismatches = validate_body(
+        scenario.response,
+        body,
+        reference.filter(|_| scenario.verify),
+    )
+    .map_err(|e| format!("unexpected body: {}", e))?;
+
+    metric.valid_response = true;
+
+    if let Some(mismatches) = mismatches {
+        metric.correct = Some(mismatches.is_empty());
+        metric.mismatches = mismatches;
+    }
+
+    Ok(())
+}
+
+/* Desserializa no tipo da resposta. Só os 4 endpoints do desafio têm
+ * gabarito (`Verify`); os outros só têm o formato conferido.
+ */
+fn validate_body(
+    kind: ResponseKind,
+    body: serde_json::Value,
+    reference: Option<&Reference>,
+) -> Result<Option<Vec<Mismatch>>, String> {
+    fn verified<T: DeserializeOwned + Verify>(
+        body: serde_json::Value,
+        reference: Option<&Reference>,
+    ) -> Result<Option<Vec<Mismatch>>, String> {
+        let parsed: T = serde_json::from_value(body).map_err(|e| e.to_string())?;
+        Ok(reference.map(|r| parsed.verify(r)))
+    }
+
+    fn parsed<T: DeserializeOwned>(
+        body: serde_json::Value,
+    ) -> Result<Option<Vec<Mismatch>>, String> {
+        serde_json::from_value::<T>(body)
+            .map(|_| None)
+            .map_err(|e| e.to_string())
+    }
+
+    match kind {
+        ResponseKind::Superusers => verified::<GetSuperusersResp>(body, reference),
+        ResponseKind::TopCountries => verified::<TopCountriesResp>(body, reference),
+        ResponseKind::TeamInsights => verified::<TeamInsightsResp>(body, reference),
+        ResponseKind::ActiveUsers => verified::<ActiveUsersResp>(body, reference),
+        ResponseKind::Leaderboard => parsed::<LeaderboardResp>(body),
+        ResponseKind::DataQuality => parsed::<DataQualityResp>(body),
+        ResponseKind::UsersDiff => parsed::<DatasetDiffResp>(body),
+        ResponseKind::Memory => parsed::<MemoryResp>(body),
+        ResponseKind::Json | ResponseKind::Any => Ok(None),
+    }
+}
+
+/* O alvo padrão é o próprio servidor, montado a partir da config do
+ * Rocket (Rocket.toml / ROCKET_ADDRESS / ROCKET_PORT). Quando ele
+ * escuta em todas as interfaces (0.0.0.0 ou ::) não dá pra conectar
+ * nesse endereço, então vai pelo loopback.
+ */
+pub fn base_url_from_config(config: &rocket::Config) -> String {
+    let address = match config.address {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    };
+
+    // SocketAddr já coloca o IPv6 entre colchetes
+    format!("http://{}", SocketAddr::new(address, config.port))
+}
+
+// `?target=localhost:8001` ou `?target=http://10.0.0.2:8000/`
+pub fn parse_target(target: &str) -> Result<String, String> {
+    let target = target.trim().trim_end_matches('/');
+
+    let url = if target.contains("://") {
+        target.to_owned()
+    } else {
+        format!("http://{}", target)
+    };
+
+    match reqwest::Url::parse(&url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(url),
+        Ok(_) => Err(format!(
+            "invalid target '{}': expected an http(s) URL",
+            target
+        )),
+        Err(e) => Err(format!("invalid target '{}': {}", target, e)),
+    }
+}
+
+/* `parse_target` pros handlers HTTP: o `?target=` vem de qualquer
+ * cliente anônimo, então só vale loopback/localhost ou um host do
+ * `allowed_targets` - senão o servidor vira um proxy pra rede interna.
+ * A CLI (`evaluate`) continua aceitando qualquer URL.
+ */
+pub fn parse_local_target(target: &str, allowed: &[String]) -> Result<String, String> {
+    let url = parse_target(target)?;
+    let parsed = reqwest::Url::parse(&url).map_err(|e| e.to_string())?;
+
+    // O IPv6 vem entre colchetes no `host_str`
+    let host = parsed.host_str().unwrap_or_default();
+    let local = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => host.eq_ignore_ascii_case("localhost"),
+    };
+
+    if local || allowed.iter().any(|a| a.eq_ignore_ascii_case(host)) {
+        Ok(url)
+    } else {
+        Err(format!(
+            "target '{}' is not allowed: only loopback or hosts in `allowed_targets`",
+            host
+        ))
+    }
+}
+
+async fn run_checks(
+    base_url: String,
+    scenarios: &Scenarios,
+    reference: Option<&Reference>,
+) -> EvaluationResp {
+    // Um de cada vez, senão um endpoint atrapalha a latência do outro
+    let mut tested_endpoints = BTreeMap::new();
+    for scenario in scenarios.0.iter() {
+        let (name, metric) = check(&base_url, scenario, reference, latency::REQUEST_TIMEOUT).await;
+        tested_endpoints.insert(name, metric);
+    }
+
+    let passed = tested_endpoints.values().filter(|m| m.passed()).count();
+    let failed = tested_endpoints.len() - passed;
+
+    EvaluationResp {
+        run_id: None,
+        target: base_url,
+        passed,
+        failed,
+        score: passed as f32 / tested_endpoints.len() as f32 * 100.0,
+        success: failed == 0,
+        tested_endpoints,
+        baseline_run_id: None,
+        regressions: Vec::new(),
+    }
+}
+
+/* Mesmas checagens, mas de fora do servidor (CLI `evaluate`): sem
+ * dataset local não tem gabarito, então só status, formato e latência.
+ */
+pub async fn evaluate_target(base_url: &str, scenarios: &Scenarios) -> EvaluationResp {
+    run_checks(base_url.to_owned(), scenarios, None).await
+}
+
+async fn evaluate(
+    target: Option<&str>,
+    config: &rocket::Config,
+    root: &Root,
+    scenarios: &Scenarios,
+    history: &EvaluationHistory,
+    app_config: &AppConfig,
+) -> Result<EvaluationResp, BadRequest<String>> {
+    // Na autoavaliação os resultados também são conferidos contra um
+    // gabarito calculado do dataset atual (`correctness.rs`). Com
+    // `?target=` o dataset de lá pode ser outro, então só checa o formato.
+    let (base_url, reference) = match target {
+        Some(target) => (
+            parse_local_target(target, &app_config.evaluation.allowed_targets)
+                .map_err(BadRequest)?,
+            None,
+        ),
+        None => (
+            base_url_from_config(config),
+            Some(Reference::from_users(&root.snapshot().users)),
+        ),
+    };
+
+    let report = run_checks(base_url, scenarios, reference.as_ref()).await;
+
+    Ok(history.record(report, |report, baseline_id, baseline| {
+        report.regressions = detect_regressions(baseline, report, &app_config.evaluation);
+        report.baseline_run_id = Some(baseline_id);
+    }))
+}
+
+#[get("/evaluation?<target>")]
+pub(crate) async fn get_evaluation(
+    target: Option<&str>,
+    format: ReportFormat,
+    config: &rocket::Config,
+    root: &State<Root>,
+    scenarios: &State<Scenarios>,
+    history: &State<EvaluationHistory>,
+    app_config: &State<AppConfig>,
+) -> Result<EvaluationOutput, BadRequest<String>> {
+    // Ele deve executar uma autoavaliação dos principais
+    // endpoints da API e retornar um relatório de pontuação.
+    //
+    // A avaliação deve testar:
+    //
+    // Se o status retornado é 200
+    // O tempo em milisegundos de resposta
+    // Se o retorno é um JSON válido
+    // Esse endpoint pode rodar scripts de teste embutidos
+    // no próprio projeto e retornar um JSON com os resultados.
+    // Ele será utilizado para validar a entrega de forma
+    // automática e rápida.
+    //
+    // `?target=` avalia outra instância (ex: uma segunda cópia local
+    // em outra porta) em vez do próprio servidor.
+    //
+    // Cada execução vai pro `/evaluation/history` e é comparada com a
+    // anterior (mesmo alvo) pra apontar regressões.
+    //
+    // `?format=junit|markdown` (ou o `Accept`) muda o formato da saída.
+    let report = evaluate(target, config, root, scenarios, history, app_config).await?;
+
+    Ok(EvaluationOutput::render(report, format))
+}
+
+#[post("/evaluation?<target>", data = "<scenarios>")]
+pub(crate) async fn post_evaluation(
+    target: Option<&str>,
+    format: ReportFormat,
+    scenarios: Result<Scenarios, String>,
+    config: &rocket::Config,
+    root: &State<Root>,
+    history: &State<EvaluationHistory>,
+    app_config: &State<AppConfig>,
+) -> Result<EvaluationOutput, BadRequest<String>> {
+    // Mesma avaliação, mas com os cenários vindo no corpo.
+    // JSON com `Content-Type: application/json`; qualquer outro é TOML.
+    let scenarios = scenarios.map_err(BadRequest)?;
+
+    let report = evaluate(target, config, root, &scenarios, history, app_config).await?;
+
+    Ok(EvaluationOutput::render(report, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    use crate::tests::{_build_app_with_empty_root, _use_root_state};
+
+    fn _response(status: u16, body: &str) -> TimedResponse {
+        TimedResponse {
+            status,
+            body: body.as_bytes().to_vec(),
+            latency: Latency::default(),
+        }
+    }
+
+    #[test]
+    fn test_check_response() {
+        let scenario = Scenario::new("/active-users-per-day", ResponseKind::ActiveUsers);
+
+        let ok = check_response(
+            _response(
+                200,
+                r#"{"timestamp": "", "execution_time_ms": 3, "logins": []}"#,
+            ),
+            &scenario,
+            None,
+        );
+        assert!(ok.valid_response);
+        assert_eq!(ok.server_time_ms, Some(3));
+        assert_eq!(ok.error, None);
+
+        let server_error = check_response(_response(500, ""), &scenario, None);
+        assert!(!server_error.valid_response);
+        assert_eq!(
+            server_error.error.unwrap(),
+            "unexpected status 500 (expected 200)"
+        );
+
+        let not_json = check_response(_response(200, "<html>"), &scenario, None);
+        assert!(not_json.error.unwrap().starts_with("invalid JSON"));
+
+        let wrong_shape = check_response(
+            _response(200, r#"{"timestamp": "", "execution_time_ms": 3}"#),
+            &scenario,
+            None,
+        );
+        assert!(!wrong_shape.valid_response);
+        assert_eq!(wrong_shape.server_time_ms, Some(3));
+        assert!(wrong_shape.error.unwrap().starts_with("unexpected body"));
+    }
+
+    #[test]
+    fn test_check_response_scenarios() {
+        let not_found = Scenario {
+            expected_status: 404,
+            ..Scenario::new("/users/diff", ResponseKind::UsersDiff)
+        };
+        let metric = check_response(_response(404, ""), &not_found, None);
+        assert!(metric.passed());
+
+        let slow = Scenario {
+            latency_budget_ms: Some(1),
+            ..Scenario::new("/anything", ResponseKind::Json)
+        };
+        let mut resp = _response(200, "[]");
+        resp.latency.total_us = 1_500;
+
+        let metric = check_response(resp, &slow, None);
+        assert!(metric.valid_response);
+        assert_eq!(metric.within_budget, Some(false));
+        assert!(!metric.passed());
+    }
+
+    #[rocket::async_test]
+    async fn test_get_evaluation_unreachable_target() {
+        // Pega uma porta livre e solta: ninguém vai estar escutando nela
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let target = format!("127.0.0.1:{}", port);
+
+        let rocket = _build_app_with_empty_root();
+        let root = _use_root_state(&rocket);
+
+        let scenarios = Scenarios::builtin();
+        let history = EvaluationHistory::new(10, None);
+
+        let resp = evaluate(
+            Some(&target),
+            &rocket::Config::default(),
+            root,
+            &scenarios,
+            &history,
+            &AppConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!((resp.passed, resp.failed), (0, 4));
+        assert_eq!(resp.score, 0.0);
+        assert!(!resp.success);
+        assert!(resp.tested_endpoints.values().all(|m| {
+            m.status.is_none() && m.error.as_ref().unwrap().starts_with("request failed")
+        }));
+        assert_eq!(resp.run_id, Some(1));
+        assert_eq!(history.entries(None, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_detect_regressions() {
+        let scenario = Scenario::new("/anything", ResponseKind::Json);
+        let with_latency = |status: u16, total_us: u64| {
+            let mut resp = _response(status, "{}");
+            resp.latency.total_us = total_us;
+            check_response(resp, &scenario, None)
+        };
+
+        let report = |metrics: [(&str, RouteMetric); 3]| EvaluationResp {
+            run_id: None,
+            target: String::from("http://127.0.0.1:8000"),
+            passed: 0,
+            failed: 0,
+            score: 0.0,
+            success: false,
+            tested_endpoints: metrics
+                .into_iter()
+                .map(|(name, metric)| (name.to_owned(), metric))
+                .collect(),
+            baseline_run_id: None,
+            regressions: Vec::new(),
+        };
+
+        let baseline = report([
+            ("/a", with_latency(200, 10_000)),
+            ("/b", with_latency(200, 10_000)),
+            ("/c", with_latency(200, 100)),
+        ]);
+        let current = report([
+            ("/a", with_latency(500, 10_000)),
+            ("/b", with_latency(200, 15_000)),
+            // +200%, mas só 200 µs: abaixo do `regression_min_delta_us`
+            ("/c", with_latency(200, 300)),
+        ]);
+
+        let regressions = detect_regressions(&baseline, &current, &EvaluationConfig::default());
+
+        assert_eq!(
+            regressions,
+            vec![
+                Regression::Failing {
+                    endpoint: "/a".into(),
+                    error: Some("unexpected status 500 (expected 200)".into()),
+                },
+                Regression::Latency {
+                    endpoint: "/b".into(),
+                    baseline_us: 10_000,
+                    current_us: 15_000,
+                    change_pct: 50.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluation_target() {
+        let figment = rocket::Config::figment()
+            .merge(("address", "0.0.0.0"))
+            .merge(("port", 8123));
+        let config = rocket::Config::from(figment);
+
+        assert_eq!(base_url_from_config(&config), "http://127.0.0.1:8123");
+
+        let config = rocket::Config {
+            address: Ipv6Addr::UNSPECIFIED.into(),
+            ..config
+        };
+        assert_eq!(base_url_from_config(&config), "http://[::1]:8123");
+
+        assert_eq!(
+            parse_target("localhost:8001/").unwrap(),
+            "http://localhost:8001"
+        );
+        assert_eq!(
+            parse_target("https://10.0.0.2:8000").unwrap(),
+            "https://10.0.0.2:8000"
+        );
+        assert!(parse_target("ftp://localhost").is_err());
+        assert!(parse_target("").is_err());
+
+        // Nos handlers, só loopback ou o que estiver no `allowed_targets`
+        let allowed = vec![String::from("staging.local")];
+        assert!(parse_local_target("localhost:8001", &[]).is_ok());
+        assert!(parse_local_target("http://127.0.0.2:8001", &[]).is_ok());
+        assert!(parse_local_target("http://[::1]:8001", &[]).is_ok());
+        assert!(parse_local_target("https://10.0.0.2:8000", &[]).is_err());
+        assert!(parse_local_target("http://169.254.169.254/", &[]).is_err());
+        assert!(parse_local_target("http://STAGING.local:8000", &allowed).is_ok());
+
+        let rocket = _build_app_with_empty_root()
+            .manage(EvaluationHistory::new(10, None))
+            .manage(Scenarios::builtin())
+            .mount("/", routes![get_evaluation]);
+        let client = rocket::local::blocking::Client::untracked(rocket).unwrap();
+
+        let resp = client
+            .get("/evaluation?target=http://10.0.0.2:8000")
+            .dispatch();
+        assert_eq!(resp.status(), rocket::http::Status::BadRequest);
+        assert!(resp.into_string().unwrap().contains("not allowed"));
+    }
+
+    #[rocket::async_test]
+    async fn test_check_timeout() {
+        // Aceita a conexão e nunca responde
+        let listener = rocket::tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        rocket::tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let scenario = Scenario::new("/superusers", ResponseKind::Superusers);
+        let (_, metric) = check(
+            &format!("http://{}", addr),
+            &scenario,
+            None,
+            Duration::from_millis(200),
+        )
+        .await;
+
+        assert_eq!(metric.status, None);
+        assert!(!metric.passed());
+        assert!(metric.error.unwrap().starts_with("request failed"));
+    }
+}
@@ -0,0 +1,455 @@
+use rocket::http::{Accept, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::serde::json::Json;
+use std::fmt::Write;
+
+use super::{EvaluationResp, Regression, RouteMetric};
+use crate::negotiation::{Binary, BinaryFormat};
+
+/* Outros formatos pro relatório da avaliação, pra ligar nos dashboards
+ * de teste: JUnit XML (um testcase por checagem) e uma tabela em
+ * Markdown. O `?format=` ganha do header `Accept`; sem nenhum dos dois
+ * continua sendo JSON. MessagePack e CBOR também, igual aos endpoints
+ * de análise (veja `negotiation.rs`).
+ */
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat {
+    Json,
+    #[field(value = "junit")]
+    #[field(value = "xml")]
+    Junit,
+    #[field(value = "markdown")]
+    #[field(value = "md")]
+    Markdown,
+    MsgPack,
+    Cbor,
+}
+
+#[derive(Responder)]
+pub enum EvaluationOutput {
+    Json(Json<EvaluationResp>),
+    #[response(content_type = "xml")]
+    Junit(String),
+    #[response(content_type = "text/markdown")]
+    Markdown(String),
+    Binary(Binary<EvaluationResp>),
+}
+
+impl ReportFormat {
+    pub fn negotiate(format: Option<ReportFormat>, accept: Option<&Accept>) -> Self {
+        if let Some(format) = format {
+            return format;
+        }
+
+        let Some(accept) = accept else {
+            return ReportFormat::Json;
+        };
+
+        let media = accept.preferred().media_type();
+
+        match (media.top().as_str(), media.sub().as_str()) {
+            ("application" | "text", "xml") => ReportFormat::Junit,
+            ("text", "markdown") => ReportFormat::Markdown,
+            ("application", "msgpack" | "x-msgpack") => ReportFormat::MsgPack,
+            ("application", "cbor") => ReportFormat::Cbor,
+            _ => ReportFormat::Json,
+        }
+    }
+}
+
+// Request guard: junta o `?format=` e o `Accept` num parâmetro só
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ReportFormat {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.query_value::<ReportFormat>("format") {
+            Some(Err(_)) => Outcome::Error((Status::BadRequest, ())),
+            Some(Ok(format)) => Outcome::Success(format),
+            None => Outcome::Success(ReportFormat::negotiate(None, request.accept())),
+        }
+    }
+}
+
+impl EvaluationOutput {
+    pub fn render(report: EvaluationResp, format: ReportFormat) -> Self {
+        match format {
+            ReportFormat::Json => EvaluationOutput::Json(Json(report)),
+            ReportFormat::Junit => EvaluationOutput::Junit(junit(&report)),
+            ReportFormat::Markdown => EvaluationOutput::Markdown(markdown(&report)),
+            ReportFormat::MsgPack => {
+                EvaluationOutput::Binary(Binary(report, BinaryFormat::MsgPack))
+            }
+            ReportFormat::Cbor => EvaluationOutput::Binary(Binary(report, BinaryFormat::Cbor)),
+        }
+    }
+}
+
+// Por que a checagem falhou, em uma linha
+fn failure_message(metric: &RouteMetric) -> Option<String> {
+    if metric.passed() {
+        return None;
+    }
+
+    if let Some(error) = &metric.error {
+        return Some(error.clone());
+    }
+
+    if !metric.mismatches.is_empty() {
+        return Some(format!(
+            "{} mismatch(es) against the reference",
+            metric.mismatches.len()
+        ));
+    }
+
+    Some(String::from("check failed"))
+}
+
+fn total_secs(metric: &RouteMetric) -> f64 {
+    metric.latency.map(|l| l.total_us).unwrap_or(0) as f64 / 1_000_000.0
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn describe_regression(regression: &Regression) -> String {
+    match regression {
+        Regression::Failing { endpoint, error } => format!(
+            "{}: now failing ({})",
+            endpoint,
+            error.as_deref().unwrap_or("check failed")
+        ),
+        Regression::Latency {
+            endpoint,
+            baseline_us,
+            current_us,
+            change_pct,
+        } => format!(
+            "{}: latency {:.2} ms -> {:.2} ms (+{:.1}%)",
+            endpoint,
+            *baseline_us as f64 / 1000.0,
+            *current_us as f64 / 1000.0,
+            change_pct
+        ),
+    }
+}
+
+pub fn junit(report: &EvaluationResp) -> String {
+    let tests = report.tested_endpoints.len();
+    let time: f64 = report.tested_endpoints.values().map(total_secs).sum();
+
+    // `write!` numa String não falha, por isso os `let _`
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"evaluation\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">",
+        tests, report.failed, time
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">",
+        xml_escape(&report.target),
+        tests,
+        report.failed,
+        time
+    );
+
+    xml.push_str("    <properties>\n");
+    let _ = writeln!(
+        xml,
+        "      <property name=\"score\" value=\"{:.1}\"/>",
+        report.score
+    );
+    if let Some(run_id) = report.run_id {
+        let _ = writeln!(
+            xml,
+            "      <property name=\"run_id\" value=\"{}\"/>",
+            run_id
+        );
+    }
+    xml.push_str("    </properties>\n");
+
+    for (endpoint, metric) in report.tested_endpoints.iter() {
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"evaluation\" name=\"{}\" time=\"{:.6}\"",
+            xml_escape(endpoint),
+            total_secs(metric)
+        );
+
+        let Some(message) = failure_message(metric) else {
+            xml.push_str("/>\n");
+            continue;
+        };
+
+        let details: String = metric
+            .mismatches
+            .iter()
+            .map(|m| format!("{}\n", m))
+            .collect();
+
+        let _ = writeln!(
+            xml,
+            ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+            xml_escape(&message),
+            xml_escape(&details)
+        );
+    }
+
+    if !report.regressions.is_empty() {
+        let regressions: Vec<String> = report.regressions.iter().map(describe_regression).collect();
+
+        let _ = writeln!(
+            xml,
+            "    <system-out>Regressions:\n{}</system-out>",
+            xml_escape(&regressions.join("\n"))
+        );
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+
+    xml
+}
+
+// Tabela em texto puro, pro terminal (CLI `evaluate`)
+pub fn table(report: &EvaluationResp) -> String {
+    let header = ["ENDPOINT", "STATUS", "LATENCY (ms)", "RESULT", "DETAILS"];
+
+    let rows: Vec<[String; 5]> = report
+        .tested_endpoints
+        .iter()
+        .map(|(endpoint, metric)| {
+            let message = failure_message(metric);
+
+            [
+                endpoint.clone(),
+                metric
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| String::from("-")),
+                metric
+                    .latency
+                    .map(|l| format!("{:.2}", l.total_us as f64 / 1000.0))
+                    .unwrap_or_else(|| String::from("-")),
+                String::from(if message.is_none() { "pass" } else { "FAIL" }),
+                message.unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_row = |cells: [&str; 5]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        let _ = writeln!(out, "{}", line.join("  ").trim_end());
+    };
+
+    push_row(header);
+    for row in rows.iter() {
+        push_row([&row[0], &row[1], &row[2], &row[3], &row[4]]);
+    }
+
+    let _ = writeln!(
+        out,
+        "\n{} - {}/{} checks passed (score {:.1}%) against {}",
+        if report.success { "PASS" } else { "FAIL" },
+        report.passed,
+        report.tested_endpoints.len(),
+        report.score,
+        report.target
+    );
+
+    out
+}
+
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+pub fn markdown(report: &EvaluationResp) -> String {
+    let mut md = String::new();
+
+    let _ = writeln!(md, "# Evaluation: {}\n", report.target);
+    let _ = writeln!(
+        md,
+        "**{}** - {}/{} checks passed (score {:.1}%)\n",
+        if report.success { "PASS" } else { "FAIL" },
+        report.passed,
+        report.tested_endpoints.len(),
+        report.score
+    );
+
+    md.push_str("| Endpoint | Status | Latency (ms) | Server (ms) | Result | Details |\n");
+    md.push_str("| --- | ---: | ---: | ---: | --- | --- |\n");
+
+    for (endpoint, metric) in report.tested_endpoints.iter() {
+        let status = metric
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| String::from("-"));
+        let latency = metric
+            .latency
+            .map(|l| format!("{:.2}", l.total_us as f64 / 1000.0))
+            .unwrap_or_else(|| String::from("-"));
+        let server = metric
+            .server_time_ms
+            .map(|ms| ms.to_string())
+            .unwrap_or_else(|| String::from("-"));
+        let message = failure_message(metric);
+
+        let _ = writeln!(
+            md,
+            "| `{}` | {} | {} | {} | {} | {} |",
+            md_cell(endpoint),
+            status,
+            latency,
+            server,
+            if message.is_none() { "pass" } else { "FAIL" },
+            md_cell(message.as_deref().unwrap_or(""))
+        );
+    }
+
+    if !report.regressions.is_empty() {
+        let _ = writeln!(
+            md,
+            "\n## Regressions (vs run #{})\n",
+            report.baseline_run_id.unwrap_or_default()
+        );
+
+        for regression in report.regressions.iter() {
+            let _ = writeln!(md, "- {}", describe_regression(regression));
+        }
+    }
+
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::MediaType;
+    use std::collections::BTreeMap;
+
+    use crate::latency::{Latency, TimedResponse};
+    use crate::scenarios::{ResponseKind, Scenario};
+
+    fn _report() -> EvaluationResp {
+        let scenario = Scenario::new("/anything", ResponseKind::Json);
+        let check = |status: u16| {
+            let resp = TimedResponse {
+                status,
+                body: b"{}".to_vec(),
+                latency: Latency {
+                    connect_us: 100,
+                    ttfb_us: 1_000,
+                    total_us: 1_500,
+                },
+            };
+            super::super::check_response(resp, &scenario, None)
+        };
+
+        EvaluationResp {
+            run_id: Some(2),
+            target: String::from("http://127.0.0.1:8000"),
+            passed: 1,
+            failed: 1,
+            score: 50.0,
+            success: false,
+            tested_endpoints: BTreeMap::from([
+                (String::from("/ok"), check(200)),
+                (String::from("/broken?a=1&b=<2>"), check(500)),
+            ]),
+            baseline_run_id: Some(1),
+            regressions: vec![Regression::Failing {
+                endpoint: String::from("/broken?a=1&b=<2>"),
+                error: Some(String::from("unexpected status 500 (expected 200)")),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_negotiate_format() {
+        let xml = Accept::from(MediaType::XML);
+        let markdown = Accept::from(MediaType::new("text", "markdown"));
+
+        assert_eq!(ReportFormat::negotiate(None, None), ReportFormat::Json);
+        assert_eq!(
+            ReportFormat::negotiate(None, Some(&xml)),
+            ReportFormat::Junit
+        );
+        assert_eq!(
+            ReportFormat::negotiate(None, Some(&markdown)),
+            ReportFormat::Markdown
+        );
+        assert_eq!(
+            ReportFormat::negotiate(Some(ReportFormat::Json), Some(&xml)),
+            ReportFormat::Json
+        );
+        assert_eq!(
+            ReportFormat::negotiate(None, Some(&Accept::from(MediaType::MsgPack))),
+            ReportFormat::MsgPack
+        );
+        assert_eq!(
+            ReportFormat::negotiate(
+                None,
+                Some(&Accept::from(MediaType::new("application", "cbor")))
+            ),
+            ReportFormat::Cbor
+        );
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&_report());
+
+        assert!(xml.contains("<testsuites name=\"evaluation\" tests=\"2\" failures=\"1\""));
+        assert!(
+            xml.contains("<testcase classname=\"evaluation\" name=\"/ok\" time=\"0.001500\"/>")
+        );
+        assert!(xml.contains("name=\"/broken?a=1&amp;b=&lt;2&gt;\""));
+        assert!(xml.contains("<failure message=\"unexpected status 500 (expected 200)\">"));
+        assert!(xml.contains("<system-out>Regressions:"));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = markdown(&_report());
+
+        assert!(md.starts_with("# Evaluation: http://127.0.0.1:8000\n"));
+        assert!(md.contains("**FAIL** - 1/2 checks passed (score 50.0%)"));
+        assert!(md.contains("| `/ok` | 200 | 1.50 | - | pass |  |"));
+        assert!(md.contains("## Regressions (vs run #1)"));
+    }
+
+    #[test]
+    fn test_table() {
+        let table = table(&_report());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].starts_with("ENDPOINT"));
+        assert!(lines[1].starts_with("/broken"));
+        // Colunas alinhadas: o status começa na mesma posição em todas as linhas
+        assert_eq!(lines[0].find("STATUS"), lines[2].find("200"));
+        assert!(
+            table.ends_with(
+                "FAIL - 1/2 checks passed (score 50.0%) against http://127.0.0.1:8000\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use arrow::array::{
+    ArrayRef, BooleanArray, ListArray, RecordBatch, StringArray, StructArray, UInt8Array,
+    UInt16Array,
+};
+use arrow::buffer::OffsetBuffer;
+use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::FileWriter;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rocket::State;
+use rocket::http::{ContentType, Header, Status};
+use std::sync::Arc;
+
+use crate::{Root, User};
+
+/* Export do dataset atual pro pessoal de dados abrir no pandas/polars/
+ * duckdb sem precisar parsear o JSON gigante de novo.
+ *
+ *   $ curl -o users.parquet 'localhost:8000/users/export?format=parquet'
+ *   $ curl -o users.arrow 'localhost:8000/users/export?format=arrow'
+ *
+ * Uma linha por usuário, com os mesmos nomes de campo do JSON. O time
+ * vira uma struct, e `team.projects` e `logs` viram listas de struct -
+ * nada de tabela separada pra dar join. O `.arrow` é o formato de
+ * arquivo do Arrow IPC (o "Feather v2"); o Parquet sai com snappy.
+ */
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Arrow,
+    Parquet,
+}
+
+// Linhas por RecordBatch (e por row group, no Parquet)
+const BATCH_ROWS: usize = 64 * 1024;
+
+fn project_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("completed", DataType::Boolean, false),
+    ])
+}
+
+fn log_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("date", DataType::Utf8, false),
+        Field::new("action", DataType::Utf8, false),
+    ])
+}
+
+fn team_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("leader", DataType::Boolean, false),
+        Field::new_list(
+            "projects",
+            Field::new_list_field(DataType::Struct(project_fields()), false),
+            false,
+        ),
+    ])
+}
+
+fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("age", DataType::UInt8, false),
+        Field::new("score", DataType::UInt16, false),
+        Field::new("active", DataType::Boolean, false),
+        Field::new("country", DataType::Utf8, false),
+        // Só é `null` quando o país não bateu com nenhum alias
+        Field::new("country_code", DataType::Utf8, true),
+        Field::new("team", DataType::Struct(team_fields()), false),
+        Field::new_list(
+            "logs",
+            Field::new_list_field(DataType::Struct(log_fields()), false),
+            false,
+        ),
+    ]))
+}
+
+// `from_iter_values` quer saber o tamanho do iterator antes, e o
+// `flat_map` das listas não sabe
+fn flattened_strings<'a>(values: impl Iterator<Item = &'a str>) -> StringArray {
+    values.map(Some).collect()
+}
+
+fn record_batch(schema: &SchemaRef, users: &[User]) -> Result<RecordBatch, ArrowError> {
+    let projects = users.iter().flat_map(|u| u.team.projects.iter());
+    let projects = StructArray::try_new(
+        project_fields(),
+        vec![
+            Arc::new(flattened_strings(projects.clone().map(|p| p.name.as_str()))) as ArrayRef,
+            Arc::new(BooleanArray::from(
+                projects.map(|p| p.completed).collect::<Vec<bool>>(),
+            )),
+        ],
+        None,
+    )?;
+    let projects = ListArray::try_new(
+        Arc::new(Field::new_list_field(
+            DataType::Struct(project_fields()),
+            false,
+        )),
+        OffsetBuffer::from_lengths(users.iter().map(|u| u.team.projects.len())),
+        Arc::new(projects),
+        None,
+    )?;
+
+    let team = StructArray::try_new(
+        team_fields(),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                users.iter().map(|u| u.team.name.as_str()),
+            )) as ArrayRef,
+            Arc::new(BooleanArray::from_iter(
+                users.iter().map(|u| Some(u.team.leader)),
+            )),
+            Arc::new(projects),
+        ],
+        None,
+    )?;
+
+    let logs = users.iter().flat_map(|u| u.logs.iter());
+    let logs = StructArray::try_new(
+        log_fields(),
+        vec![
+            Arc::new(flattened_strings(logs.clone().map(|l| l.date.as_str()))) as ArrayRef,
+            Arc::new(flattened_strings(logs.map(|l| l.action.as_str()))),
+        ],
+        None,
+    )?;
+    let logs = ListArray::try_new(
+        Arc::new(Field::new_list_field(DataType::Struct(log_fields()), false)),
+        OffsetBuffer::from_lengths(users.iter().map(|u| u.logs.len())),
+        Arc::new(logs),
+        None,
+    )?;
+
+    RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                users.iter().map(|u| u.id.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                users.iter().map(|u| u.name.as_str()),
+            )),
+            Arc::new(UInt8Array::from_iter_values(users.iter().map(|u| u.age))),
+            Arc::new(UInt16Array::from_iter_values(users.iter().map(|u| u.score))),
+            Arc::new(BooleanArray::from_iter(
+                users.iter().map(|u| Some(u.active)),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                users.iter().map(|u| u.country.as_str()),
+            )),
+            Arc::new(StringArray::from_iter(
+                users.iter().map(|u| u.country_code.as_deref()),
+            )),
+            Arc::new(team),
+            Arc::new(logs),
+        ],
+    )
+}
+
+fn write_arrow(users: &[User]) -> Result<Vec<u8>, ArrowError> {
+    let schema = schema();
+    let mut writer = FileWriter::try_new(Vec::new(), &schema)?;
+
+    for chunk in users.chunks(BATCH_ROWS) {
+        writer.write(&record_batch(&schema, chunk)?)?;
+    }
+
+    writer.into_inner()
+}
+
+fn write_parquet(users: &[User]) -> Result<Vec<u8>, ArrowError> {
+    let schema = schema();
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(BATCH_ROWS)
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(props))?;
+
+    for chunk in users.chunks(BATCH_ROWS) {
+        writer.write(&record_batch(&schema, chunk)?)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+pub(crate) fn export(users: &[User], format: ExportFormat) -> Result<Vec<u8>, ArrowError> {
+    match format {
+        ExportFormat::Arrow => write_arrow(users),
+        ExportFormat::Parquet => write_parquet(users),
+    }
+}
+
+#[derive(Responder)]
+pub struct ExportResp {
+    body: Vec<u8>,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+#[get("/users/export?<format>")]
+pub fn get_users_export(format: ExportFormat, root: &State<Root>) -> Result<ExportResp, Status> {
+    let dataset = root.snapshot();
+
+    let body = export(&dataset.users, format).map_err(|e| {
+        log::error!("export {:?} failed: {}", format, e);
+        Status::InternalServerError
+    })?;
+
+    let (content_type, extension) = match format {
+        ExportFormat::Arrow => (
+            ContentType::new("application", "vnd.apache.arrow.file"),
+            "arrow",
+        ),
+        ExportFormat::Parquet => (
+            ContentType::new("application", "vnd.apache.parquet"),
+            "parquet",
+        ),
+    };
+
+    // A versão no nome do arquivo, pra não confundir dois exports
+    let disposition = Header::new(
+        "Content-Disposition",
+        format!(
+            "attachment; filename=\"users-v{}.{}\"",
+            dataset.version, extension
+        ),
+    );
+
+    Ok(ExportResp {
+        body,
+        content_type,
+        disposition,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::UInt16Type;
+    use arrow::ipc::reader::FileReader;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use rocket::local::blocking::Client;
+    use std::io::Cursor;
+
+    use crate::tests::{_build_app_with_fixture, _load_fixture_users};
+
+    fn _check_batch(batch: &RecordBatch, users: &[User]) {
+        assert_eq!(batch.num_rows(), users.len());
+
+        let ids = batch.column_by_name("id").unwrap().as_string::<i32>();
+        let scores = batch
+            .column_by_name("score")
+            .unwrap()
+            .as_primitive::<UInt16Type>();
+        assert_eq!(ids.value(0), users[0].id);
+        assert_eq!(scores.value(3), users[3].score);
+
+        let team = batch.column_by_name("team").unwrap().as_struct();
+        let projects = team.column_by_name("projects").unwrap().as_list::<i32>();
+        assert_eq!(projects.value(1).len(), users[1].team.projects.len());
+
+        let logs = batch.column_by_name("logs").unwrap().as_list::<i32>();
+        let first_logs = logs.value(0);
+        let dates = first_logs.as_struct().column(0).as_string::<i32>();
+        assert_eq!(dates.value(0), users[0].logs[0].date.as_str());
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let users = _load_fixture_users("usuarios_10").unwrap();
+
+        let arrow = export(&users, ExportFormat::Arrow).unwrap();
+        let batches: Vec<RecordBatch> = FileReader::try_new(Cursor::new(arrow), None)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        _check_batch(&batches[0], &users);
+
+        // O reader do Parquet quer `bytes::Bytes` ou um arquivo
+        let path = std::env::temp_dir().join(format!("export-{}.parquet", std::process::id()));
+        std::fs::write(&path, export(&users, ExportFormat::Parquet).unwrap()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.collect::<Result<_, _>>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(batches[0].schema().fields(), schema().fields());
+        _check_batch(&batches[0], &users);
+    }
+
+    #[test]
+    fn test_get_users_export() {
+        let rocket = _build_app_with_fixture("usuarios_10").mount("/", routes![get_users_export]);
+        let client = Client::untracked(rocket).unwrap();
+
+        let resp = client.get("/users/export?format=parquet").dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        assert!(
+            resp.headers()
+                .get_one("Content-Disposition")
+                .unwrap()
+                .ends_with(".parquet\"")
+        );
+        assert!(resp.into_bytes().unwrap().starts_with(b"PAR1"));
+
+        let resp = client.get("/users/export?format=arrow").dispatch();
+        assert_eq!(
+            resp.content_type(),
+            Some(ContentType::new("application", "vnd.apache.arrow.file"))
+        );
+        assert!(resp.into_bytes().unwrap().starts_with(b"ARROW1"));
+
+        let resp = client.get("/users/export?format=xlsx").dispatch();
+        assert_eq!(resp.status(), Status::UnprocessableEntity);
+    }
+}
@@ -0,0 +1,544 @@
+use chrono::{Days, NaiveDate};
+use clap::ValueEnum;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::{self, Write};
+
+use crate::intern::Symbol;
+use crate::{TeamProject, User, UserLog, UserTeam};
+
+/* Gerador de usuários sintéticos.
+ *
+ * O repo só tem o `usuarios_10.json`; os de 100k e 1MM citados nos
+ * comentários nunca foram versionados (65MiB+). Com isso aqui dá pra
+ * gerar o mesmo dataset em qualquer máquina: mesma seed, mesmos
+ * usuários, byte a byte.
+ *
+ *   $ challengeresult generate --count 100000 --seed 42 --output usuarios_100k.json
+ *   $ challengeresult generate --count 1000000 --format ndjson --output usuarios_1m.ndjson.gz
+ *
+ * O PRNG é um SplitMix64 próprio (e não o `rand`) justamente pra
+ * sequência não mudar quando alguém atualizar uma dependência.
+ */
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum GeneratorFormat {
+    // Um array, igual ao export que o `POST /users` recebe
+    Json,
+    // Um usuário por linha
+    Ndjson,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub count: usize,
+    // Nome do país (como vem no export) => peso
+    pub countries: Vec<(String, u32)>,
+    // Nome do time => peso
+    pub teams: Vec<(String, u32)>,
+    pub projects: Vec<String>,
+    // Normal truncada em min_score..=max_score
+    pub score_mean: f64,
+    pub score_stddev: f64,
+    pub min_score: u16,
+    pub max_score: u16,
+    // Uniforme, inclusive
+    pub min_age: u8,
+    pub max_age: u8,
+    pub active_ratio: f64,
+    pub leader_ratio: f64,
+    pub completed_ratio: f64,
+    pub max_projects: usize,
+    pub max_logs: usize,
+    pub date_from: NaiveDate,
+    pub date_to: NaiveDate,
+}
+
+fn weighted(values: &[(&str, u32)]) -> Vec<(String, u32)> {
+    values.iter().map(|(v, w)| (v.to_string(), *w)).collect()
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        // Mais ou menos a cara do `usuarios_10.json`
+        GeneratorConfig {
+            seed: 42,
+            count: 1_000,
+            countries: weighted(&[
+                ("Brasil", 30),
+                ("Estados Unidos", 15),
+                ("Argentina", 10),
+                ("Canadá", 8),
+                ("Japão", 8),
+                ("Índia", 8),
+                ("França", 7),
+                ("Alemanha", 7),
+                ("Portugal", 7),
+            ]),
+            teams: weighted(&[
+                ("Frontend Avengers", 3),
+                ("Backend Ninjas", 3),
+                ("Fullstack Force", 2),
+                ("UX Wizards", 1),
+                ("Data Wizards", 1),
+            ]),
+            projects: [
+                "Dashboard",
+                "Landing Page",
+                "API Pública",
+                "Sistema Interno",
+                "Mobile App",
+                "E-commerce",
+            ]
+            .map(String::from)
+            .to_vec(),
+            score_mean: 600.0,
+            score_stddev: 250.0,
+            // O sample vai até 1040, então 1000 não é o teto. 1100 é o
+            // mesmo `max_score` do `[default.data_quality]`
+            min_score: 0,
+            max_score: 1100,
+            min_age: 18,
+            max_age: 65,
+            active_ratio: 0.8,
+            leader_ratio: 0.1,
+            completed_ratio: 0.5,
+            max_projects: 3,
+            max_logs: 6,
+            date_from: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            date_to: NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+        }
+    }
+}
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice",
+    "Ana",
+    "Antônio",
+    "Arthur",
+    "Beatriz",
+    "Bernardo",
+    "Clarice",
+    "Davi",
+    "Elisa",
+    "Enzo",
+    "Gabriel",
+    "Heitor",
+    "Helena",
+    "Isabela",
+    "João",
+    "Laura",
+    "Lucas",
+    "Manuela",
+    "Maria",
+    "Miguel",
+    "Nicolas",
+    "Pedro",
+    "Pietra",
+    "Rafael",
+    "Sarah",
+    "Sophia",
+    "Valentina",
+    "Vicente",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Araújo",
+    "Barbosa",
+    "Carvalho",
+    "Cavalcanti",
+    "Costa",
+    "da Paz",
+    "das Neves",
+    "Ferreira",
+    "Gomes",
+    "Lima",
+    "Martins",
+    "Moreira",
+    "Oliveira",
+    "Pereira",
+    "Porto",
+    "Ribeiro",
+    "Rocha",
+    "Santos",
+    "Silva",
+    "Souza",
+    "Viana",
+];
+
+const ACTIONS: &[&str] = &["login", "logout"];
+
+// SplitMix64: https://prng.di.unimi.it/splitmix64.c
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // [0, n)
+    fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    fn between(&mut self, min: u64, max: u64) -> u64 {
+        min + self.below(max - min + 1)
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+
+    fn pick<'a>(&mut self, values: &'a [&str]) -> &'a str {
+        values[self.below(values.len() as u64) as usize]
+    }
+
+    // Box-Muller
+    fn normal(&mut self, mean: f64, stddev: f64) -> f64 {
+        let u1 = 1.0 - self.unit();
+        let u2 = self.unit();
+
+        mean + stddev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+// Sorteio por peso: soma acumulada + busca binária
+struct Weighted {
+    values: Vec<Symbol>,
+    cumulative: Vec<u64>,
+}
+
+impl Weighted {
+    fn new(field: &str, values: &[(String, u32)]) -> Result<Self, String> {
+        let mut total = 0;
+        let mut cumulative = Vec::with_capacity(values.len());
+
+        for (_, weight) in values {
+            total += *weight as u64;
+            cumulative.push(total);
+        }
+
+        if total == 0 {
+            return Err(format!("{}: at least one value with weight > 0", field));
+        }
+
+        Ok(Weighted {
+            values: values.iter().map(|(v, _)| Symbol::intern(v)).collect(),
+            cumulative,
+        })
+    }
+
+    fn pick(&self, rng: &mut Rng) -> Symbol {
+        let ticket = rng.below(*self.cumulative.last().unwrap());
+        self.values[self.cumulative.partition_point(|c| *c <= ticket)].clone()
+    }
+}
+
+/* `Brasil=30,Argentina=10,Chile` - sem peso vale 1 */
+pub fn parse_weights(value: &str) -> Result<Vec<(String, u32)>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|item| match item.rsplit_once('=') {
+            Some((name, weight)) => weight
+                .trim()
+                .parse()
+                .map(|w| (name.trim().to_owned(), w))
+                .map_err(|_| format!("invalid weight in '{}'", item)),
+            None => Ok((item.to_owned(), 1)),
+        })
+        .collect()
+}
+
+struct UserFactory<'a> {
+    config: &'a GeneratorConfig,
+    rng: Rng,
+    countries: Weighted,
+    teams: Weighted,
+    projects: Vec<Symbol>,
+    actions: Vec<Symbol>,
+    // Datas já internadas, indexadas pelo offset a partir de `date_from`
+    dates: Vec<Symbol>,
+}
+
+impl<'a> UserFactory<'a> {
+    fn new(config: &'a GeneratorConfig) -> Result<Self, String> {
+        if config.min_age > config.max_age {
+            return Err(String::from("min_age must not be greater than max_age"));
+        }
+        if config.min_score > config.max_score {
+            return Err(String::from("min_score must not be greater than max_score"));
+        }
+        if config.date_from > config.date_to {
+            return Err(String::from("date_from must not be after date_to"));
+        }
+        if config.projects.is_empty() {
+            return Err(String::from("projects: at least one project"));
+        }
+
+        let days = (config.date_to - config.date_from).num_days() as u64;
+        let dates = (0..=days)
+            .map(|d| {
+                let date = config.date_from + Days::new(d);
+                Symbol::intern(&date.format("%Y-%m-%d").to_string())
+            })
+            .collect();
+
+        Ok(UserFactory {
+            config,
+            rng: Rng(config.seed),
+            countries: Weighted::new("countries", &config.countries)?,
+            teams: Weighted::new("teams", &config.teams)?,
+            projects: config.projects.iter().map(|p| Symbol::intern(p)).collect(),
+            actions: ACTIONS.iter().map(|a| Symbol::intern(a)).collect(),
+            dates,
+        })
+    }
+
+    // UUID v4 "de mentira", mas no formato certo e reproduzível
+    fn uuid(&mut self) -> String {
+        let high = self.rng.next_u64();
+        let low = self.rng.next_u64();
+
+        let high = (high & !0xF000) | 0x4000;
+        let low = (low & !(0b11 << 62)) | (0b10 << 62);
+
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xFFFF,
+            high & 0xFFFF,
+            low >> 48,
+            low & 0xFFFF_FFFF_FFFF
+        )
+    }
+
+    fn user(&mut self) -> User {
+        let config = self.config;
+        let rng = &mut self.rng;
+
+        let name = format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES));
+        let age = rng.between(config.min_age as u64, config.max_age as u64) as u8;
+        let score = rng
+            .normal(config.score_mean, config.score_stddev)
+            .round()
+            .clamp(config.min_score as f64, config.max_score as f64) as u16;
+        let active = rng.chance(config.active_ratio);
+        let country = self.countries.pick(rng);
+
+        let team_name = self.teams.pick(rng);
+        let leader = rng.chance(config.leader_ratio);
+
+        // Sem repetir projeto no mesmo usuário (Fisher-Yates parcial)
+        let project_count =
+            (rng.between(1, config.max_projects.max(1) as u64) as usize).min(self.projects.len());
+        let mut pool: Vec<usize> = (0..self.projects.len()).collect();
+        for i in 0..project_count {
+            let j = i + rng.below((pool.len() - i) as u64) as usize;
+            pool.swap(i, j);
+        }
+        let projects = pool[..project_count]
+            .iter()
+            .map(|&p| TeamProject {
+                name: self.projects[p].clone(),
+                completed: rng.chance(config.completed_ratio),
+            })
+            .collect();
+
+        let log_count = rng.between(0, config.max_logs as u64) as usize;
+        let logs = (0..log_count)
+            .map(|_| UserLog {
+                date: self.dates[rng.below(self.dates.len() as u64) as usize].clone(),
+                action: self.actions[rng.below(self.actions.len() as u64) as usize].clone(),
+            })
+            .collect();
+
+        let id = self.uuid();
+
+        User {
+            id,
+            name,
+            age,
+            score,
+            active,
+            country,
+            country_code: None,
+            team: UserTeam {
+                name: team_name,
+                leader,
+                projects,
+            },
+            logs,
+        }
+    }
+}
+
+/* Os usuários um a um, sem montar o Vec inteiro - 1MM de usuários
+ * em memória só pra escrever no disco não faz sentido.
+ */
+pub(crate) fn users(config: &GeneratorConfig) -> Result<impl Iterator<Item = User> + '_, String> {
+    let mut factory = UserFactory::new(config)?;
+
+    Ok((0..config.count).map(move |_| factory.user()))
+}
+
+fn write_users<W: Write>(
+    config: &GeneratorConfig,
+    format: GeneratorFormat,
+    mut writer: W,
+) -> io::Result<W> {
+    let users = users(config).map_err(io::Error::other)?;
+
+    if format == GeneratorFormat::Json {
+        writer.write_all(b"[\n")?;
+    }
+
+    for (i, user) in users.enumerate() {
+        if format == GeneratorFormat::Json && i > 0 {
+            writer.write_all(b",\n")?;
+        }
+
+        serde_json::to_writer(&mut writer, &user)?;
+
+        if format == GeneratorFormat::Ndjson {
+            writer.write_all(b"\n")?;
+        }
+    }
+
+    if format == GeneratorFormat::Json {
+        writer.write_all(b"\n]\n")?;
+    }
+
+    Ok(writer)
+}
+
+pub fn generate<W: Write>(
+    config: &GeneratorConfig,
+    format: GeneratorFormat,
+    gzip: bool,
+    writer: W,
+) -> io::Result<()> {
+    if gzip {
+        let encoder = GzEncoder::new(writer, Compression::default());
+        write_users(config, format, encoder)?.finish()?;
+    } else {
+        write_users(config, format, writer)?.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn _generate(config: &GeneratorConfig, format: GeneratorFormat, gzip: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        generate(config, format, gzip, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        assert_eq!(
+            parse_weights("Brasil=3, Chile ,").unwrap(),
+            vec![(String::from("Brasil"), 3), (String::from("Chile"), 1)]
+        );
+        assert!(parse_weights("Brasil=x").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let config = GeneratorConfig {
+            count: 500,
+            countries: parse_weights("Brasil=9,Chile=1").unwrap(),
+            ..Default::default()
+        };
+
+        // Mesma seed, mesmo arquivo
+        let json = _generate(&config, GeneratorFormat::Json, false);
+        assert_eq!(json, _generate(&config, GeneratorFormat::Json, false));
+
+        let users: Vec<User> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(users.len(), 500);
+
+        let brasil = users.iter().filter(|u| u.country == "Brasil").count();
+        assert!(
+            users
+                .iter()
+                .all(|u| u.country == "Brasil" || u.country == "Chile")
+        );
+        assert!(brasil > 400, "brasil = {}", brasil);
+
+        assert!(
+            users
+                .iter()
+                .all(|u| (18..=65).contains(&u.age) && u.score <= 1100)
+        );
+        assert!(users.iter().flat_map(|u| u.logs.iter()).all(|l| {
+            let date = NaiveDate::parse_from_str(&l.date, "%Y-%m-%d").unwrap();
+            date >= config.date_from && date <= config.date_to
+        }));
+
+        assert!(users.iter().all(|u| {
+            let names: std::collections::HashSet<&str> =
+                u.team.projects.iter().map(|p| p.name.as_str()).collect();
+            names.len() == u.team.projects.len()
+        }));
+
+        let ids: std::collections::HashSet<&str> = users.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(ids.len(), 500);
+
+        // NDJSON comprimido: mesmos usuários, um por linha
+        let mut ndjson = String::new();
+        GzDecoder::new(&_generate(&config, GeneratorFormat::Ndjson, true)[..])
+            .read_to_string(&mut ndjson)
+            .unwrap();
+
+        let lines: Vec<User> = ndjson
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, users);
+
+        let other_seed = GeneratorConfig {
+            seed: 7,
+            ..config.clone()
+        };
+        assert_ne!(json, _generate(&other_seed, GeneratorFormat::Json, false));
+
+        // Faixa de score do `usuarios_10.json` (100..=1040)
+        let sample_range = GeneratorConfig {
+            score_mean: 950.0,
+            min_score: 100,
+            max_score: 1040,
+            ..config.clone()
+        };
+        let users: Vec<User> =
+            serde_json::from_slice(&_generate(&sample_range, GeneratorFormat::Json, false))
+                .unwrap();
+        assert!(users.iter().all(|u| (100..=1040).contains(&u.score)));
+        assert!(users.iter().any(|u| u.score > 1000));
+
+        let inverted = GeneratorConfig {
+            min_score: 1040,
+            max_score: 100,
+            ..config
+        };
+        let mut out = Vec::new();
+        assert!(generate(&inverted, GeneratorFormat::Json, false, &mut out).is_err());
+    }
+}
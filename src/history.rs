@@ -0,0 +1,364 @@
+use chrono::Local;
+use rocket::fairing::AdHoc;
+use rocket::serde::json::Json;
+use rocket::{Build, Rocket, State};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use crate::config::AppConfig;
+use crate::evaluation::EvaluationResp;
+
+const DEFAULT_LIMIT: usize = 20;
+
+/* Histórico das execuções do `/evaluation`.
+ *
+ * Fica em memória (as últimas `history_size`) e, se tiver um
+ * `history_file` configurado, cada execução também vira uma linha de
+ * NDJSON no disco. No boot o arquivo é relido, então o histórico (e a
+ * detecção de regressão) sobrevive a um restart.
+ *
+ * O arquivo não cresce pra sempre: quando passa de 2x `history_size`
+ * linhas ele é reescrito só com as últimas `history_size` (o 2x é pra
+ * não reescrever a cada execução).
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    id: u64,
+    timestamp: String,
+    report: EvaluationResp,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EvaluationHistoryResp {
+    entries: Vec<HistoryEntry>,
+}
+
+// O arquivo e quantas linhas ele tem agora
+struct HistoryFile {
+    path: PathBuf,
+    lines: usize,
+}
+
+pub struct EvaluationHistory {
+    entries: RwLock<VecDeque<HistoryEntry>>,
+    max_entries: usize,
+    // Mutex separado: o disco não segura o lock das entradas
+    file: Option<Mutex<HistoryFile>>,
+}
+
+impl EvaluationHistory {
+    pub fn new(max_entries: usize, file: Option<PathBuf>) -> Self {
+        EvaluationHistory {
+            entries: RwLock::new(VecDeque::new()),
+            max_entries: max_entries.max(1),
+            file: file.map(|path| Mutex::new(HistoryFile { path, lines: 0 })),
+        }
+    }
+
+    pub fn load(max_entries: usize, path: PathBuf) -> Result<Self, String> {
+        let history = Self::new(max_entries, Some(path.clone()));
+
+        // Primeira execução: o arquivo ainda não existe
+        let Ok(file) = File::open(&path) else {
+            return Ok(history);
+        };
+
+        let mut entries = history.entries.write().unwrap();
+        let mut lines = 0;
+
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+            lines += 1;
+
+            let entry: HistoryEntry = serde_json::from_str(&line)
+                .map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+
+            entries.push_back(entry);
+            if entries.len() > history.max_entries {
+                entries.pop_front();
+            }
+        }
+
+        // Arquivo de uma versão que não compactava: já sai do boot enxuto
+        if lines > history.max_entries {
+            lines = compact(&path, history.max_entries)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+
+        drop(entries);
+        if let Some(file) = &history.file {
+            file.lock().unwrap().lines = lines;
+        }
+
+        Ok(history)
+    }
+
+    /* Grava a execução. `compare` recebe o relatório novo e a última
+     * execução contra o mesmo alvo (id e relatório), se tiver.
+     *
+     * A busca da base e o insert acontecem no mesmo lock: duas
+     * avaliações simultâneas não pegam a mesma base nem o mesmo id.
+     */
+    pub fn record(
+        &self,
+        mut report: EvaluationResp,
+        compare: impl FnOnce(&mut EvaluationResp, u64, &EvaluationResp),
+    ) -> EvaluationResp {
+        let mut entries = self.entries.write().unwrap();
+
+        if let Some(baseline) = entries
+            .iter()
+            .rev()
+            .find(|e| e.report.target() == report.target())
+        {
+            compare(&mut report, baseline.id, &baseline.report);
+        }
+
+        let id = entries.back().map(|e| e.id + 1).unwrap_or(1);
+        let report = report.with_run_id(id);
+
+        let entry = HistoryEntry {
+            id,
+            timestamp: format!("{:?}", Local::now()),
+            report: report.clone(),
+        };
+
+        entries.push_back(entry.clone());
+        if entries.len() > self.max_entries {
+            entries.pop_front();
+        }
+
+        // Pega o lock do arquivo antes de soltar o das entradas, pra as
+        // linhas saírem na ordem dos ids
+        let file = self.file.as_ref().map(|f| f.lock().unwrap());
+        drop(entries);
+
+        // Gravar no disco é best effort: não vale derrubar a avaliação
+        if let Some(mut file) = file
+            && let Err(e) = self.persist(&mut file, &entry)
+        {
+            log::error!("failed to persist evaluation history: {}", e);
+        }
+
+        report
+    }
+
+    fn persist(&self, file: &mut HistoryFile, entry: &HistoryEntry) -> std::io::Result<()> {
+        append_line(&file.path, entry)?;
+        file.lines += 1;
+
+        if file.lines >= self.max_entries * 2 {
+            file.lines = compact(&file.path, self.max_entries)?;
+        }
+
+        Ok(())
+    }
+
+    // Mais recente primeiro
+    pub fn entries(&self, target: Option<&str>, limit: usize) -> Vec<HistoryEntry> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|e| target.is_none_or(|t| e.report.target() == t))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+fn append_line(path: &Path, entry: &HistoryEntry) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+
+    file.write_all(&line)
+}
+
+/* Fica só com as últimas `keep` linhas; devolve quantas ficaram.
+ * Escreve num arquivo temporário e troca no final, pra um crash no
+ * meio não deixar o histórico pela metade.
+ */
+fn compact(path: &Path, keep: usize) -> std::io::Result<usize> {
+    let mut lines = VecDeque::with_capacity(keep + 1);
+
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        lines.push_back(line);
+        if lines.len() > keep {
+            lines.pop_front();
+        }
+    }
+
+    let tmp = path.with_extension("tmp");
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    for line in lines.iter() {
+        out.write_all(line.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    out.into_inner()?.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+
+    Ok(lines.len())
+}
+
+#[get("/evaluation/history?<target>&<limit>")]
+pub fn get_evaluation_history(
+    target: Option<&str>,
+    limit: Option<usize>,
+    history: &State<EvaluationHistory>,
+) -> Json<EvaluationHistoryResp> {
+    // Execuções anteriores do `/evaluation`, da mais recente pra mais
+    // antiga. `?target=` filtra pelo alvo avaliado (ex: http://127.0.0.1:8000)
+    Json(EvaluationHistoryResp {
+        entries: history.entries(target, limit.unwrap_or(DEFAULT_LIMIT)),
+    })
+}
+
+pub fn history_fairing() -> AdHoc {
+    AdHoc::try_on_ignite("Evaluation History", |rocket: Rocket<Build>| async move {
+        let config = rocket
+            .state::<AppConfig>()
+            .map(|c| c.evaluation.clone())
+            .unwrap_or_default();
+
+        let history = match config.history_file {
+            Some(path) => EvaluationHistory::load(config.history_size, PathBuf::from(path)),
+            None => Ok(EvaluationHistory::new(config.history_size, None)),
+        };
+
+        match history {
+            Ok(history) => Ok(rocket.manage(history)),
+            Err(e) => {
+                log::error!("failed to load evaluation history: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _report(target: &str) -> EvaluationResp {
+        serde_json::from_value(serde_json::json!({
+            "target": target,
+            "passed": 1,
+            "failed": 0,
+            "score": 100.0,
+            "success": true,
+            "tested_endpoints": {}
+        }))
+        .unwrap()
+    }
+
+    // Grava e devolve o id da base que o `record` achou
+    fn _record(history: &EvaluationHistory, target: &str) -> Option<u64> {
+        let mut baseline = None;
+        history.record(_report(target), |_, id, _| baseline = Some(id));
+        baseline
+    }
+
+    fn _temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.ndjson", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn _file_lines(path: &Path) -> usize {
+        std::fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn test_history_persistence() {
+        let path = _temp_path("evaluation_history");
+
+        let history = EvaluationHistory::load(2, path.clone()).unwrap();
+
+        assert_eq!(_record(&history, "http://a"), None);
+        assert_eq!(_record(&history, "http://b"), None);
+        assert_eq!(_record(&history, "http://a"), Some(1));
+        assert_eq!(history.entries(None, 10).len(), 2);
+
+        // Recarrega do disco: ids continuam e o teto vale de novo
+        let reloaded = EvaluationHistory::load(2, path.clone()).unwrap();
+        let ids: Vec<u64> = reloaded.entries(None, 10).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![3, 2]);
+        assert_eq!(reloaded.entries(Some("http://b"), 10).len(), 1);
+        assert_eq!(_file_lines(&path), 2);
+
+        let report = reloaded.record(_report("http://b"), |_, _, _| {});
+        assert_eq!(report.target(), "http://b");
+        assert_eq!(_record(&reloaded, "http://b"), Some(4));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_history_compaction() {
+        let path = _temp_path("evaluation_history_compaction");
+
+        let history = EvaluationHistory::load(3, path.clone()).unwrap();
+        for _ in 0..5 {
+            _record(&history, "http://a");
+        }
+        assert_eq!(_file_lines(&path), 5);
+
+        // Bateu 2x o teto: sobram só as 3 últimas
+        _record(&history, "http://a");
+        assert_eq!(_file_lines(&path), 3);
+
+        for _ in 0..20 {
+            _record(&history, "http://a");
+        }
+        assert!(_file_lines(&path) < 6);
+
+        let reloaded = EvaluationHistory::load(3, path.clone()).unwrap();
+        let ids: Vec<u64> = reloaded.entries(None, 10).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![26, 25, 24]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_history_concurrent_record() {
+        let path = _temp_path("evaluation_history_concurrent");
+        let history = EvaluationHistory::load(100, path.clone()).unwrap();
+        _record(&history, "http://a");
+
+        let baselines: Vec<Option<u64>> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| s.spawn(|| _record(&history, "http://a")))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // Cada execução pegou uma base diferente (a anterior a ela)
+        let mut baselines: Vec<u64> = baselines.into_iter().map(Option::unwrap).collect();
+        baselines.sort();
+        assert_eq!(baselines, (1..=8).collect::<Vec<u64>>());
+
+        // E no disco as linhas ficaram na ordem dos ids
+        let reloaded = EvaluationHistory::load(100, path.clone()).unwrap();
+        let ids: Vec<u64> = reloaded.entries(None, 100).iter().map(|e| e.id).collect();
+        assert_eq!(ids, (1..=9).rev().collect::<Vec<u64>>());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
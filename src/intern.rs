@@ -0,0 +1,267 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/* String interning.
+ *
+ * País, nome do time, projeto, ação e data do log se repetem MUITO:
+ * no sample de 100k usuários são ~100k "Brasil", ~500k "login" e por
+ * aí vai - cada um com a sua própria `String` na HEAP (24 bytes da
+ * struct + o texto + o overhead do allocator).
+ * O `Symbol` é só um ponteiro pro texto guardado uma única vez na
+ * `SymbolTable` do dataset. Ler o texto é seguir o ponteiro, sem lock
+ * nenhum, e dois Symbols da mesma tabela se comparam pelo endereço
+ * antes de olhar o texto.
+ *
+ * A tabela é de cada versão (`Dataset::symbols`): quando a versão sai
+ * do `Root` e o último request que usava ela termina, os textos vão
+ * embora junto. Antes era uma tabela global que nunca liberava nada -
+ * cada upload com datas novas crescia a memória pra sempre.
+ *
+ * `Arc<Box<str>>` e não `Arc<str>`: o ponteiro fica com 8 bytes em vez
+ * de 16, e são milhões de Symbols nos logs.
+ */
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Symbol(Arc<Box<str>>);
+
+#[derive(Default)]
+pub struct SymbolTable {
+    texts: HashSet<Symbol>,
+}
+
+thread_local! {
+    // Tabela do parse em andamento nesta thread (veja `SymbolTable::parsing`)
+    static PARSING: RefCell<Option<SymbolTable>> = const { RefCell::new(None) };
+}
+
+// Devolve a tabela anterior mesmo se o parse der panic
+struct RestoreParsing(Option<SymbolTable>);
+
+impl Drop for RestoreParsing {
+    fn drop(&mut self) {
+        PARSING.set(self.0.take());
+    }
+}
+
+impl SymbolTable {
+    pub fn intern(&mut self, value: &str) -> Symbol {
+        if let Some(symbol) = self.texts.get(value) {
+            return symbol.clone();
+        }
+
+        let symbol = Symbol(Arc::new(Box::from(value)));
+        self.texts.insert(symbol.clone());
+        symbol
+    }
+
+    /* Troca o texto do Symbol pelo da tabela. Quem foi montado fora de
+     * um parse (os usuários dos testes) passa a apontar pra cá e a
+     * cópia dele é liberada.
+     */
+    #[cfg(test)]
+    pub fn adopt(&mut self, symbol: &mut Symbol) {
+        match self.texts.get(symbol.as_str()) {
+            Some(own) if own.same(symbol) => {}
+            Some(own) => *symbol = own.clone(),
+            None => {
+                self.texts.insert(symbol.clone());
+            }
+        }
+    }
+
+    /* Roda um parse (serde_json, MessagePack...) com uma tabela nova
+     * ativa nesta thread: o "login" repetido 500k vezes vira um texto
+     * só já na leitura, sem alocar uma String por ocorrência. A tabela
+     * volta junto com o resultado, pra virar a do dataset.
+     */
+    pub fn parsing<T>(parse: impl FnOnce() -> T) -> (T, SymbolTable) {
+        let mut restore = RestoreParsing(PARSING.replace(Some(SymbolTable::default())));
+        let result = parse();
+
+        let table = PARSING.replace(restore.0.take()).unwrap_or_default();
+        (result, table)
+    }
+
+    // Quantos bytes a tabela ocupa (texto + o Arc de cada um + índice)
+    pub fn table_bytes(&self) -> usize {
+        let text: usize = self.texts.iter().map(|s| s.len()).sum();
+
+        text + self.texts.len() * (2 * size_of::<usize>() + size_of::<Box<str>>())
+            + self.texts.capacity() * size_of::<Symbol>()
+    }
+}
+
+// Pro `HashSet<Symbol>` da tabela aceitar busca por `&str`
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Symbol {
+    // Dentro de um `SymbolTable::parsing` usa a tabela do parse
+    pub fn intern(value: &str) -> Symbol {
+        PARSING.with_borrow_mut(|table| match table {
+            Some(table) => table.intern(value),
+            None => Symbol(Arc::new(Box::from(value))),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // Mesma tabela, mesmo texto => mesmo endereço
+    pub fn same(&self, other: &Symbol) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(value: &str) -> Self {
+        Symbol::intern(value)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+// Ordena pelo texto (e não pelo id) - as datas precisam sair em ordem
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.same(other) {
+            return Ordering::Equal;
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/* Interna direto do texto que o serde_json está lendo: dentro de um
+ * `SymbolTable::parsing`, quando o valor já existe nenhuma String é
+ * alocada.
+ */
+struct SymbolVisitor;
+
+impl Visitor<'_> for SymbolVisitor {
+    type Value = Symbol;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Symbol, E> {
+        Ok(Symbol::intern(value))
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+        deserializer.deserialize_str(SymbolVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let a = Symbol::intern("Frontend Avengers");
+        let b: Symbol = serde_json::from_str(r#""Frontend Avengers""#).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.as_str(), "Frontend Avengers");
+        assert_ne!(a, Symbol::intern("UX Wizards"));
+        assert_eq!(serde_json::to_string(&a).unwrap(), r#""Frontend Avengers""#);
+    }
+
+    #[test]
+    fn test_ord_by_text() {
+        let later = Symbol::intern("2025-03-31");
+        let earlier = Symbol::intern("2025-03-01");
+
+        assert!(earlier < later);
+        assert_eq!(earlier.cmp(&earlier), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_parsing_shares_text() {
+        let (dates, mut table) = SymbolTable::parsing(|| {
+            serde_json::from_str::<Vec<Symbol>>(r#"["2025-03-01", "2025-03-01", "2025-03-02"]"#)
+                .unwrap()
+        });
+
+        assert!(dates[0].same(&dates[1]));
+        assert!(!dates[0].same(&dates[2]));
+        assert_eq!(table.texts.len(), 2);
+
+        // Fora do parse cada um tem o seu texto, até a tabela adotar
+        let mut loose = Symbol::intern("2025-03-01");
+        assert!(!loose.same(&dates[0]));
+        table.adopt(&mut loose);
+        assert!(loose.same(&dates[0]));
+    }
+
+    #[test]
+    fn test_table_owns_text() {
+        let mut table = SymbolTable::default();
+        let first = table.intern("login");
+
+        let mut other = Symbol::intern("login");
+        table.adopt(&mut other);
+        assert!(first.same(&other));
+        assert_eq!(table.texts.len(), 1);
+
+        // Sem a tabela e sem os Symbols, o texto é liberado
+        let weak = Arc::downgrade(&first.0);
+        drop((table, first, other));
+        assert!(weak.upgrade().is_none());
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/* Latência medida do lado de quem chama (e não o `execution_time_ms`
+ * que o próprio endpoint reporta), em microssegundos:
+ *
+ *   connect: abrir a conexão TCP (+ TLS se for https)
+ *   ttfb:    do início até chegarem os headers da resposta
+ *   total:   do início até o último byte do body
+ *
+ * Cada medição usa um Client novo, sem pool: senão a segunda request
+ * reaproveita a conexão e o connect sai de graça.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Latency {
+    pub connect_us: u64,
+    pub ttfb_us: u64,
+    pub total_us: u64,
+}
+
+pub struct TimedResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub latency: Latency,
+}
+
+// Um endpoint que aceita a conexão e nunca responde não pode segurar
+// a avaliação pra sempre
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub async fn timed_get(url: &str, timeout: Duration) -> reqwest::Result<TimedResponse> {
+    let connect_us = Arc::new(AtomicU64::new(0));
+
+    let client = reqwest::Client::builder()
+        .pool_max_idle_per_host(0)
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .connector_layer(ConnectTimerLayer {
+            elapsed_us: connect_us.clone(),
+        })
+        .build()?;
+
+    let start = Instant::now();
+
+    let resp = client.get(url).send().await?;
+    let ttfb_us = elapsed_us(start);
+
+    let status = resp.status().as_u16();
+    let body = resp.bytes().await?.to_vec();
+    let total_us = elapsed_us(start);
+
+    Ok(TimedResponse {
+        status,
+        body,
+        latency: Latency {
+            connect_us: connect_us.load(Ordering::Relaxed),
+            ttfb_us,
+            total_us,
+        },
+    })
+}
+
+fn elapsed_us(start: Instant) -> u64 {
+    start.elapsed().as_micros() as u64
+}
+
+/* O reqwest não expõe o tempo de connect, mas aceita uma layer do
+ * tower em volta do connector. Ela só cronometra o future da conexão.
+ */
+#[derive(Clone)]
+struct ConnectTimerLayer {
+    elapsed_us: Arc<AtomicU64>,
+}
+
+impl<S> Layer<S> for ConnectTimerLayer {
+    type Service = ConnectTimer<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTimer {
+            inner,
+            elapsed_us: self.elapsed_us.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ConnectTimer<S> {
+    inner: S,
+    elapsed_us: Arc<AtomicU64>,
+}
+
+impl<S, R> Service<R> for ConnectTimer<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let start = Instant::now();
+        let connecting = self.inner.call(req);
+        let elapsed = self.elapsed_us.clone();
+
+        Box::pin(async move {
+            let conn = connecting.await;
+            elapsed.store(elapsed_us(start), Ordering::Relaxed);
+            conn
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpListener;
+
+    #[rocket::async_test]
+    async fn test_timed_get() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        rocket::tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}")
+                .await
+                .unwrap();
+        });
+
+        let resp = timed_get(&format!("http://{}/", addr), REQUEST_TIMEOUT)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, b"{}");
+        assert!(resp.latency.connect_us > 0);
+        assert!(resp.latency.connect_us <= resp.latency.ttfb_us);
+        assert!(resp.latency.ttfb_us <= resp.latency.total_us);
+    }
+}
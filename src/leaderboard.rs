@@ -0,0 +1,251 @@
+use chrono::Local;
+use rocket::State;
+use rocket::serde::json::Json;
+use rocket::tokio::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::{Root, User};
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(FromFormField, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardScope {
+    Global,
+    Country,
+    Team,
+}
+
+/* Como tratar empates:
+ * - dense: 1, 1, 2 (o próximo score pega a posição seguinte)
+ * - standard: 1, 1, 3 (o famoso "ranking de competição", pula
+ *   as posições ocupadas pelos empatados)
+ */
+#[derive(FromFormField, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RankMode {
+    Dense,
+    Standard,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    rank: usize,
+    id: String,
+    name: String,
+    score: u16,
+    country: String,
+    team: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LeaderboardGroup {
+    group: String,
+    entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LeaderboardResp {
+    timestamp: String,
+    execution_time_ms: u128,
+    scope: LeaderboardScope,
+    rank: RankMode,
+    limit: usize,
+    groups: Vec<LeaderboardGroup>,
+}
+
+/* Mesmo esquema do `get_topcountries`: score DESC e, no empate,
+ * uma chave secundária pra resposta não depender da ordem do upload.
+ * Primeiro o nome (ASC) e, se ainda empatar, o id - que é único.
+ */
+fn compare_users(a: &User, b: &User) -> Ordering {
+    b.score
+        .cmp(&a.score)
+        .then_with(|| a.name.cmp(&b.name))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+fn rank_users(mut users: Vec<&User>, rank: RankMode, limit: usize) -> Vec<LeaderboardEntry> {
+    users.sort_by(|a, b| compare_users(a, b));
+
+    let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(limit.min(users.len()));
+    let mut current_rank = 0;
+    let mut previous_score: Option<u16> = None;
+
+    for (position, u) in users.into_iter().take(limit).enumerate() {
+        if previous_score != Some(u.score) {
+            current_rank = match rank {
+                RankMode::Dense => current_rank + 1,
+                RankMode::Standard => position + 1,
+            };
+            previous_score = Some(u.score);
+        }
+
+        entries.push(LeaderboardEntry {
+            rank: current_rank,
+            id: u.id.clone(),
+            name: u.name.clone(),
+            score: u.score,
+            country: u.country_name().to_owned(),
+            team: u.team.name.to_string(),
+        });
+    }
+
+    entries
+}
+
+fn build_groups(
+    users: &[User],
+    scope: LeaderboardScope,
+    rank: RankMode,
+    limit: usize,
+) -> Vec<LeaderboardGroup> {
+    if scope == LeaderboardScope::Global {
+        return vec![LeaderboardGroup {
+            group: String::from("global"),
+            entries: rank_users(users.iter().collect(), rank, limit),
+        }];
+    }
+
+    // BTreeMap pra já sair ordenado pelo nome do grupo
+    let grouped: BTreeMap<&str, Vec<&User>> = users.iter().fold(BTreeMap::new(), |mut acc, u| {
+        let key = match scope {
+            LeaderboardScope::Country => u.country_name(),
+            _ => u.team.name.as_str(),
+        };
+        acc.entry(key).or_insert_with(Vec::new).push(u);
+        acc
+    });
+
+    grouped
+        .into_iter()
+        .map(|(group, members)| LeaderboardGroup {
+            group: group.to_owned(),
+            entries: rank_users(members, rank, limit),
+        })
+        .collect()
+}
+
+#[get("/leaderboard?<limit>&<scope>&<rank>")]
+pub fn get_leaderboard(
+    limit: Option<usize>,
+    scope: Option<LeaderboardScope>,
+    rank: Option<RankMode>,
+    root: &State<Root>,
+) -> Json<LeaderboardResp> {
+    // Top-N usuários por score: global, por país ou por time.
+    // Query params opcionais: ?limit=10&scope=global|country|team&rank=dense|standard
+    let start_time = Instant::now();
+
+    // Só lê: o snapshot basta, sem clonar o dataset inteiro
+    let dataset = root.snapshot();
+    let users = &dataset.users;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let scope = scope.unwrap_or(LeaderboardScope::Global);
+    let rank = rank.unwrap_or(RankMode::Dense);
+
+    let groups = build_groups(users, scope, rank, limit);
+
+    Json(LeaderboardResp {
+        timestamp: format!("{:?}", Local::now()),
+        execution_time_ms: start_time.elapsed().as_millis(),
+        scope,
+        rank,
+        limit,
+        groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{_build_app_with_fixture, _use_root_state};
+
+    fn _user(id: &str, name: &str, score: u16, country: &str, team: &str) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": name,
+            "age": 30,
+            "score": score,
+            "active": true,
+            "country": country,
+            "team": { "name": team, "leader": false, "projects": [] },
+            "logs": []
+        }))
+        .unwrap()
+    }
+
+    fn _ranks(entries: &[LeaderboardEntry]) -> Vec<(usize, &str)> {
+        entries.iter().map(|e| (e.rank, e.id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_get_leaderboard() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let resp = get_leaderboard(Some(3), None, None, state).0;
+
+        assert_eq!(resp.scope, LeaderboardScope::Global);
+        assert_eq!(resp.groups.len(), 1);
+        assert_eq!(
+            resp.groups[0]
+                .entries
+                .iter()
+                .map(|e| (e.rank, e.name.as_str(), e.score))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "Clarice Porto", 1040),
+                (2, "Ana Sophia Araújo", 859),
+                (3, "Nicolas Pereira", 804),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rank_users_ties() {
+        let users = [
+            _user("d", "Bruno", 800, "Brasil", "A"),
+            _user("a", "Carla", 900, "Brasil", "A"),
+            _user("c", "Ana", 900, "Brasil", "A"),
+            _user("b", "Ana", 900, "Brasil", "A"),
+            _user("e", "Davi", 700, "Brasil", "A"),
+        ];
+
+        let dense = rank_users(users.iter().collect(), RankMode::Dense, 10);
+        assert_eq!(
+            _ranks(&dense),
+            vec![(1, "b"), (1, "c"), (1, "a"), (2, "d"), (3, "e")]
+        );
+
+        let standard = rank_users(users.iter().collect(), RankMode::Standard, 10);
+        assert_eq!(
+            _ranks(&standard),
+            vec![(1, "b"), (1, "c"), (1, "a"), (4, "d"), (5, "e")]
+        );
+    }
+
+    #[test]
+    fn test_build_groups_per_team() {
+        let users = vec![
+            _user("a", "Ana", 500, "Brasil", "UX Wizards"),
+            _user("b", "Bia", 900, "Japão", "Data Wizards"),
+            _user("c", "Caio", 700, "Brasil", "UX Wizards"),
+        ];
+
+        let groups = build_groups(&users, LeaderboardScope::Team, RankMode::Dense, 1);
+
+        assert_eq!(
+            groups
+                .iter()
+                .map(|g| (g.group.as_str(), _ranks(&g.entries)))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Data Wizards", vec![(1, "b")]),
+                ("UX Wizards", vec![(1, "c")]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1296 @@
+#[macro_use]
+extern crate rocket;
+
+use chrono::Local;
+use columnar::{ColumnarUsers, TeamStats, merge_counts, merge_team_stats};
+use config::AppConfig;
+use countries::{CountryRegistry, UnmappedCountry};
+use intern::{Symbol, SymbolTable};
+use negotiation::{BinaryFormat, Negotiated, ResponseFormat};
+use parallel::Parallelism;
+use regions::{CountryGrouping, RegionMap, RegionSummary};
+use rocket::data::{self, Data, FromData, Limits};
+use rocket::fairing::AdHoc;
+use rocket::form::Form;
+use rocket::http::{ContentType, Status};
+use rocket::request::Request;
+use rocket::response::stream::TextStream;
+use rocket::serde::json::Json;
+use rocket::tokio::time::Instant;
+use rocket::{Build, Either, Rocket, State};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub mod bench;
+mod columnar;
+mod config;
+mod correctness;
+mod countries;
+mod diff;
+pub mod evaluation;
+mod export;
+pub mod generator;
+mod history;
+mod intern;
+mod latency;
+mod leaderboard;
+mod load_test;
+mod negotiation;
+mod parallel;
+mod quality;
+mod regions;
+pub mod report;
+pub mod scenarios;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct TeamProject {
+    name: Symbol,
+    completed: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct UserTeam {
+    name: Symbol,
+    leader: bool,
+    projects: Vec<TeamProject>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct UserLog {
+    date: Symbol,
+    action: Symbol,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct User {
+    id: String,
+    name: String,
+    age: u8,
+    score: u16,
+    active: bool,
+    country: Symbol,
+    /* Não vem no export: é preenchido no upload (veja `countries.rs`).
+     * Fica `None` quando o país não bate com nenhum alias conhecido.
+     */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    country_code: Option<String>,
+    team: UserTeam,
+    logs: Vec<UserLog>,
+}
+
+impl User {
+    // Nome 'oficial' do país (pt) quando mapeado; senão o valor do export.
+    fn country_name(&self) -> &str {
+        self.country_code
+            .as_deref()
+            .and_then(countries::display_name)
+            .unwrap_or(self.country.as_str())
+    }
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(crate = "rocket::serde")]
+struct CreateUsersResp {
+    message: String,
+    user_count: usize,
+    unmapped_countries: Vec<UnmappedCountry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "rocket::serde")]
+struct GetSuperusersResp {
+    timestamp: String,
+    #[serde(serialize_with = "negotiation::serialize_millis")]
+    execution_time_ms: u128,
+    user_count: usize,
+    data: Vec<User>,
+}
+
+/* Cada upload gera uma nova versão do dataset. Guardamos a versão
+ * anterior também, pra conseguir comparar o que mudou (veja `diff.rs`).
+ */
+struct Dataset {
+    version: u64,
+    users: Vec<User>,
+    // Mesmos usuários, em colunas - é o que os relatórios usam
+    columns: ColumnarUsers,
+    // O texto de todos os `Symbol` desta versão (veja `intern.rs`)
+    symbols: SymbolTable,
+    aggregates: OnceLock<Aggregates>,
+}
+
+/* Os relatórios 'default' (sem query param) só mudam quando chega um
+ * upload novo. Então calculamos uma vez por versão do dataset e
+ * guardamos aqui - quando o `Root::update` troca a versão, o cache
+ * antigo vai embora junto com ela. Não precisa invalidar nada na mão.
+ */
+struct Aggregates {
+    countries: Vec<CountrySummary>,
+    teams: Vec<TeamInsight>,
+    logins: Vec<ActiveUserLogin>,
+}
+
+/* Pros usuários montados fora de um `SymbolTable::parsing` (os dos
+ * testes): junta os Symbols deles numa tabela só.
+ */
+#[cfg(test)]
+fn collect_symbols(users: &mut [User]) -> SymbolTable {
+    let mut symbols = SymbolTable::default();
+
+    for u in users.iter_mut() {
+        symbols.adopt(&mut u.country);
+        symbols.adopt(&mut u.team.name);
+
+        for p in u.team.projects.iter_mut() {
+            symbols.adopt(&mut p.name);
+        }
+        for l in u.logs.iter_mut() {
+            symbols.adopt(&mut l.date);
+            symbols.adopt(&mut l.action);
+        }
+    }
+
+    symbols
+}
+
+impl Dataset {
+    // `symbols`: a tabela de onde vieram os Symbols dos usuários
+    fn new(version: u64, users: Vec<User>, mut symbols: SymbolTable) -> Dataset {
+        let columns = ColumnarUsers::from_users(&users, &mut symbols);
+
+        Dataset {
+            version,
+            users,
+            columns,
+            symbols,
+            aggregates: OnceLock::new(),
+        }
+    }
+
+    // Lazy: quem chegar primeiro calcula, o resto espera e reaproveita
+    fn aggregates(&self, parallelism: &Parallelism) -> &Aggregates {
+        self.aggregates.get_or_init(|| Aggregates {
+            countries: count_countries(&self.columns, parallelism),
+            teams: build_team_insights(&self.columns, parallelism),
+            logins: count_logins_per_day(&self.columns, parallelism),
+        })
+    }
+
+    /* O que cada endpoint devolve em cima do dataset. Fica aqui pra o
+     * `report` da CLI responder exatamente igual aos handlers.
+     */
+
+    // Só o que passa no filtro é clonado, não o dataset inteiro
+    fn superusers(&self) -> Vec<User> {
+        self.users
+            .iter()
+            .filter(|u| is_superuser(u))
+            .cloned()
+            .collect()
+    }
+
+    fn top_countries(&self, parallelism: &Parallelism) -> Vec<CountrySummary> {
+        self.aggregates(parallelism)
+            .countries
+            .iter()
+            .take(TOP_COUNTRIES)
+            .cloned()
+            .collect()
+    }
+
+    // O total por dia vem do cache; o `min` só filtra em cima dele
+    fn active_users(&self, parallelism: &Parallelism, min: Option<u16>) -> Vec<ActiveUserLogin> {
+        let min_ = min.unwrap_or(0) as usize;
+
+        self.aggregates(parallelism)
+            .logins
+            .iter()
+            .filter(|l| l.total >= min_)
+            .cloned()
+            .collect()
+    }
+}
+
+/* As versões guardadas, da mais antiga pra atual (a última).
+ * Cada upload empurra uma nova e, passando de `max`, a mais antiga sai.
+ */
+struct Versions {
+    datasets: VecDeque<Arc<Dataset>>,
+    max: usize,
+}
+
+/* Antes isso aqui era um `AtomicPtr<Vec<User>>` com `Box::into_raw` e
+ * `Box::from_raw` na mão (eu aprendi muito sobre a HEAP com isso, juro).
+ * O problema: o `update` liberava o ponteiro antigo enquanto um request
+ * podia estar clonando ele. Agora cada versão fica num `Arc` - quem já
+ * pegou o snapshot segura a memória até terminar, e o `RwLock` só é
+ * usado pra trocar o ponteiro. Saiu do TODO, finalmente!
+ *
+ * Saiba mais:
+ * https://doc.rust-lang.org/book/ch16-03-shared-state.html
+ */
+struct Root {
+    versions: RwLock<Versions>,
+}
+
+impl Root {
+    fn new() -> Root {
+        Root::with_versions(config::DatasetConfig::default().versions)
+    }
+
+    // `max_versions`: quantas versões guardar, contando a atual (mínimo 2)
+    fn with_versions(max_versions: usize) -> Root {
+        Root {
+            versions: RwLock::new(Versions {
+                datasets: VecDeque::from([Arc::new(Dataset::new(
+                    0,
+                    Vec::new(),
+                    SymbolTable::default(),
+                ))]),
+                max: max_versions.max(2),
+            }),
+        }
+    }
+
+    #[cfg(test)]
+    fn from_users(users: Vec<User>) -> Root {
+        let root = Root::new();
+        root.update(users);
+        root
+    }
+
+    #[cfg(test)]
+    fn update(&self, mut new_users: Vec<User>) {
+        let symbols = collect_symbols(&mut new_users);
+        self.update_with_symbols(new_users, symbols);
+    }
+
+    fn update_with_symbols(&self, new_users: Vec<User>, symbols: SymbolTable) {
+        let mut versions = self.versions.write().unwrap();
+
+        let version = versions.datasets.back().map_or(0, |d| d.version) + 1;
+        versions
+            .datasets
+            .push_back(Arc::new(Dataset::new(version, new_users, symbols)));
+
+        if versions.datasets.len() > versions.max {
+            versions.datasets.pop_front();
+        }
+    }
+
+    fn snapshot(&self) -> Arc<Dataset> {
+        self.versions
+            .read()
+            .unwrap()
+            .datasets
+            .back()
+            .unwrap()
+            .clone()
+    }
+
+    // `None` se a versão nunca existiu ou já saiu da memória
+    fn version(&self, version: u64) -> Option<Arc<Dataset>> {
+        self.versions
+            .read()
+            .unwrap()
+            .datasets
+            .iter()
+            .find(|d| d.version == version)
+            .cloned()
+    }
+
+    fn retained_versions(&self) -> Vec<u64> {
+        self.versions
+            .read()
+            .unwrap()
+            .datasets
+            .iter()
+            .map(|d| d.version)
+            .collect()
+    }
+
+    #[cfg(test)]
+    fn get_users(&self) -> Vec<User> {
+        self.snapshot().users.clone()
+    }
+}
+
+fn root_fairing() -> AdHoc {
+    AdHoc::on_ignite("Dataset Versions", |rocket| async move {
+        let versions = rocket
+            .state::<AppConfig>()
+            .map(|c| c.dataset.versions)
+            .unwrap_or(config::DatasetConfig::default().versions);
+
+        rocket.manage(Root::with_versions(versions))
+    })
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[derive(FromForm, Debug)]
+struct Upload {
+    file: String,
+}
+
+/* O corpo do `POST /users`: o multipart de sempre (campo `file` com o
+ * JSON) ou, pelo `Content-Type`, o export direto em MessagePack/CBOR.
+ */
+enum UsersUpload {
+    Json(String),
+    Binary(Vec<User>, SymbolTable),
+}
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for UsersUpload {
+    type Error = String;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let Some(format) = req.content_type().and_then(BinaryFormat::from_content_type) else {
+            return match Form::<Upload>::from_data(req, data).await {
+                data::Outcome::Success(upload) => {
+                    data::Outcome::Success(UsersUpload::Json(upload.into_inner().file))
+                }
+                data::Outcome::Error((status, errors)) => {
+                    data::Outcome::Error((status, errors.to_string()))
+                }
+                data::Outcome::Forward(f) => data::Outcome::Forward(f),
+            };
+        };
+
+        let limit = req.limits().get("bytes").unwrap_or(Limits::BYTES);
+
+        let bytes = match data.open(limit).into_bytes().await {
+            Ok(bytes) if bytes.is_complete() => bytes.into_inner(),
+            Ok(_) => {
+                let error = format!("upload larger than {}", limit);
+                return data::Outcome::Error((Status::PayloadTooLarge, error));
+            }
+            Err(e) => return data::Outcome::Error((Status::BadRequest, e.to_string())),
+        };
+
+        match SymbolTable::parsing(|| format.decode(&bytes)) {
+            (Ok(users), symbols) => data::Outcome::Success(UsersUpload::Binary(users, symbols)),
+            (Err(e), _) => data::Outcome::Error((Status::UnprocessableEntity, e)),
+        }
+    }
+}
+
+#[post("/users", data = "<upload>")]
+fn post_users(
+    upload: UsersUpload,
+    root: &State<Root>,
+    countries: &State<CountryRegistry>,
+    config: &State<AppConfig>,
+    parallelism: &State<Parallelism>,
+) -> std::io::Result<Json<CreateUsersResp>> {
+    /* FOI MUITO DIFÍCIL FAZER ESTE MÉTODO!
+     * Tem algumas formas de processar um multipart request:
+     * - Podemos processar o request Raw - aí precisaríamos
+     *   de uma biblioteca pra poder converter os form fields
+     *   em algum dado válido
+     * - Utilizar o esquema de form. O problema é que aparentemente
+     *   só funciona via `TempFile` (vou adicionar um TODO pra
+     *   testar usando String ou byte_array hehehe)
+     *   ==> DONE (deu certo!!!).
+     *
+     * Pois bem, um outro desafio foi configurar o arquivo Rocket.toml
+     * para o framework aceitar arquivos muito grandes (o sample com
+     * 100k usuários tem ~65MiB).
+     *
+     * Bom, o resultado do código são as gambiarras abaixo (acredite,
+     * sem ajuda de LLM hahaha - talvez por isso não ficou tão bom).
+     * ==> AGORA FICOU BOM <3! hehehehehe
+     */
+    let (users_len, unmapped_countries) = match upload {
+        UsersUpload::Json(file) => ingest(&file, root, countries)?,
+        UsersUpload::Binary(users, symbols) => ingest_users(users, symbols, root, countries),
+    };
+
+    // Modo eager: já deixa os relatórios prontos antes do primeiro GET
+    if config.cache.eager {
+        root.snapshot().aggregates(parallelism);
+    }
+
+    Ok(Json(CreateUsersResp {
+        message: String::from("Arquivo recebido com sucesso"),
+        user_count: users_len,
+        unmapped_countries,
+    }))
+}
+
+/* Parse, normalização dos países e nova versão do dataset. Fora do
+ * handler pra o benchmark (`bench.rs`) medir exatamente esse caminho.
+ */
+fn ingest(
+    file: &str,
+    root: &Root,
+    countries: &CountryRegistry,
+) -> serde_json::Result<(usize, Vec<UnmappedCountry>)> {
+    let (users, symbols) = SymbolTable::parsing(|| serde_json::from_str::<Vec<User>>(file));
+
+    Ok(ingest_users(users?, symbols, root, countries))
+}
+
+// O mesmo, pra quem já chega decodificado (MessagePack/CBOR)
+fn ingest_users(
+    mut users: Vec<User>,
+    symbols: SymbolTable,
+    root: &Root,
+    countries: &CountryRegistry,
+) -> (usize, Vec<UnmappedCountry>) {
+    let users_len = users.len();
+
+    // "Brasil", "Brazil" e "brasil" viram todos BR
+    let unmapped_countries = countries.normalize(&mut users);
+
+    /* Ah, aqui foi uma prova dos 30 hehehe (pedi ajuda
+     * ao Claude).
+     * Fiz o codigo abaixo somente com o `let users` e
+     * o root.update(users), mas o código quebrava no
+     * `users_count` da resposta.
+     * A desgraça aconteceu pois o compiler faz o 'move'
+     * da variável `users` no root.update() - não passamos
+     * o &users, então não é borrow, né!?
+     * Pois bem, ao fazer users.len() de uma variável que
+     * não existe, o código quebra!
+     * Tinha algumas formas de resolver, inclusive refatorando
+     * o código pra funcionar tudo via borrow (muita mudança),
+     * ou então salvar o users_len em uma variável antes de
+     * chamar o root.update() - achei mais inteligente.
+     */
+    root.update_with_symbols(users, symbols);
+
+    (users_len, unmapped_countries)
+}
+
+// Quantos países o `/top-countries` devolve
+const TOP_COUNTRIES: usize = 5;
+
+// Filtro: score >= 900 e active = true
+fn is_superuser(u: &User) -> bool {
+    u.score >= 900 && u.active
+}
+
+// Quanto de NDJSON juntar antes de mandar pro cliente
+const NDJSON_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Serialize)]
+struct SuperusersSummary {
+    timestamp: String,
+    execution_time_ms: u128,
+    user_count: usize,
+}
+
+#[derive(Serialize)]
+struct SuperusersSummaryLine {
+    summary: SuperusersSummary,
+}
+
+/* `/superusers` em NDJSON: um usuário por linha, escrito conforme o
+ * filtro vai achando, e no final uma linha `{"summary": {...}}` com a
+ * contagem e o tempo. Pega o snapshot (um `Arc`), então não clona os
+ * usuários como o JSON normal faz.
+ *
+ * As linhas vão em blocos de ~64 KiB - uma escrita por usuário deixava
+ * a resposta bem mais lenta que o JSON.
+ */
+fn stream_superusers(root: &Root) -> TextStream![String] {
+    let start_time = Instant::now();
+    let dataset = root.snapshot();
+
+    TextStream! {
+        let mut user_count = 0;
+        let mut chunk = String::with_capacity(NDJSON_CHUNK_BYTES);
+
+        for u in dataset.users.iter().filter(|u| is_superuser(u)) {
+            user_count += 1;
+            chunk.push_str(&serde_json::to_string(u).unwrap());
+            chunk.push('\n');
+
+            if chunk.len() >= NDJSON_CHUNK_BYTES {
+                yield std::mem::replace(&mut chunk, String::with_capacity(NDJSON_CHUNK_BYTES));
+            }
+        }
+
+        let summary = SuperusersSummaryLine {
+            summary: SuperusersSummary {
+                timestamp: format!("{:?}", Local::now()),
+                execution_time_ms: start_time.elapsed().as_millis(),
+                user_count,
+            },
+        };
+        chunk.push_str(&serde_json::to_string(&summary).unwrap());
+        chunk.push('\n');
+
+        yield chunk;
+    }
+}
+
+#[get("/superusers")]
+fn get_superusers(
+    format: ResponseFormat,
+    root: &State<Root>,
+) -> Either<(ContentType, TextStream![String]), Negotiated<GetSuperusersResp>> {
+    if format == ResponseFormat::Ndjson {
+        return Either::Left((
+            ContentType::new("application", "x-ndjson"),
+            stream_superusers(root),
+        ));
+    }
+
+    // Filtro: score >= 900 e active = true
+    // Retorna os dados e o tempo de processamento da requisição.
+    let start_time = Instant::now();
+
+    let dataset = root.snapshot();
+    let users = &dataset.users;
+
+    /* Este código abaixo tem um glitch:
+     * Cara, perdi muito tempo tentando resolver,
+     * mas deu certo!
+     * Inicialmente eu tentei muitas vezes fazer
+     * `users.iter().filter(..).collect()`, e tentava
+     * converter o código todo pra usar `&` (isso é anotação
+     * de borrow, não de ref - no final é a msm coisa, mas pra rust
+     * é importante entender).
+     * O problema é que eu teria que reescrever tudo, inclusive
+     * os DTO do serializers, e o 'serde' eu descobri que não
+     * lida bem com refs.
+     * Aí eu pedi ajuda pro meu amigo Claude e tudo se resolveu
+     * hahahaha - brincadeira. Eu sempre pergunto o que eu to
+     * fazendo de errado antes mesmo da solução, e se ele sugerir
+     * algo, me explicar como.
+     * O `iter` itera sobre as refs dos itens. Então se você chamar
+     * `collect()``, ele vai te devolver um Vec das refs, e não
+     * da estrutura.
+     * O que precisamos fazer é uma cópia do resultado do filter para
+     * então chamar o `collect()`.
+     * Ah, o serde ainda não serializa Iterators (infelizmente).
+     * */
+    let superusers = dataset.superusers();
+
+    log::debug!("users len: {}; capacity: {}", users.len(), users.capacity());
+
+    /* NOTA DO EDITOR:
+     * `start_time.elapsed()` <3 - achei fofo hahaha
+     */
+    let elapsed_time = start_time.elapsed();
+
+    Either::Right(Negotiated(
+        GetSuperusersResp {
+            timestamp: format!("{:?}", Local::now()),
+            execution_time_ms: elapsed_time.as_millis(),
+            user_count: superusers.len(),
+            data: superusers,
+        },
+        format,
+    ))
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct CountrySummary {
+    country: String,
+    code: Option<String>,
+    total: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TopCountriesResp {
+    timestamp: String,
+    #[serde(serialize_with = "negotiation::serialize_millis")]
+    execution_time_ms: u128,
+    countries: Vec<CountrySummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    regions: Vec<RegionSummary>,
+}
+
+/* Contagem por país, já ordenada (total DESC, país ASC).
+ * O handler corta os 5 primeiros; o diff (`diff.rs`) usa a lista inteira.
+ */
+fn count_countries(columns: &ColumnarUsers, parallelism: &Parallelism) -> Vec<CountrySummary> {
+    /* Antes era um fold num HashMap<String, usize> em cima dos usuários.
+     * Agora o país já vem como id do dicionário da representação
+     * colunar, então a contagem é só um array indexado pelo id
+     * (e cada chunk do map/reduce devolve o seu array pra somar).
+     */
+    let mut sorted: Vec<(u32, usize)> = parallelism
+        .map_reduce(
+            columns.len(),
+            |users| columns.country_counts(users),
+            merge_counts,
+        )
+        .into_iter()
+        .enumerate()
+        .filter(|(_, total)| *total > 0)
+        .map(|(id, total)| (id as u32, total))
+        .collect();
+
+    sorted.sort_by(|(akey, aval), (bkey, bval)| {
+        /* Encontrei um glitch aqui:
+         * Descobri que os vetores gerados a partir de um
+         * HashMap sao promiscuos, aleatorios. Entao quando
+         * total empata, a ordem dos objetos ficam aleatorias.
+         * Ex: Imagina o cenario no qual temos Brasil com 1
+         * usuario e Argentina com 1. A ordenacao vai acontecer
+         * de acordo com a primeira ocorrencia, mas como a
+         * ordem eh aleatoria, entao a primeira ocorrencia
+         * pode ser Brasil ou Argentina.
+         * Em um panorama geral tudo bem, mas como iremos
+         * fazer um corte dos 5 primeiros paises, neste caso
+         * quem aparecera pode ser um pais bem aleatorio.
+         * Para tornar o resultado um pouco mais deterministico
+         * colocamos uma 'chave secundaria' de comparacao.
+         * Se der empate, vence o nome do pais em ASC.
+         */
+        let cmp_val = bval.cmp(aval);
+        if cmp_val == std::cmp::Ordering::Equal {
+            return columns
+                .country_dict
+                .get(*akey)
+                .cmp(columns.country_dict.get(*bkey));
+        }
+        cmp_val
+    });
+
+    sorted
+        .into_iter()
+        .map(|(id, total)| CountrySummary {
+            country: columns.country_dict.get(id).to_owned(),
+            code: columns.country_codes[id as usize].clone(),
+            total,
+        })
+        .collect()
+}
+
+#[get("/top-countries?<group>")]
+fn get_topcountries(
+    group: Option<CountryGrouping>,
+    format: ResponseFormat,
+    root: &State<Root>,
+    region_map: &State<RegionMap>,
+    parallelism: &State<Parallelism>,
+) -> Negotiated<TopCountriesResp> {
+    // Agrupa os superusuários por país.
+    // Retorna os 5 países com maior número de superusuários.
+    // Query param opcional: ?group=continent|region soma os países por
+    // continente ou pelas regiões do Rocket.toml (campo `regions`).
+    let start_time = Instant::now();
+
+    let dataset = root.snapshot();
+
+    let all_countries = &dataset.aggregates(parallelism).countries;
+
+    // Os roll-ups saem da contagem por país que já está em cache
+    let regions = match group.unwrap_or(CountryGrouping::Country) {
+        CountryGrouping::Country => Vec::new(),
+        CountryGrouping::Continent => regions::rollup(all_countries.clone(), regions::continent_of),
+        CountryGrouping::Region => {
+            regions::rollup(all_countries.clone(), |code| region_map.region_of(code))
+        }
+    };
+
+    let countries = dataset.top_countries(parallelism);
+
+    Negotiated(
+        TopCountriesResp {
+            timestamp: format!("{:?}", Local::now()),
+            execution_time_ms: start_time.elapsed().as_millis(),
+            countries,
+            regions,
+        },
+        format,
+    )
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct TeamInsight {
+    team: String,
+    total_members: usize,
+    leaders: usize,
+    completed_projects: usize,
+    active_percentage: f32,
+
+    /* Maaaaaaanooo, demorei muito pra sacar
+     * este esquema. O skip salvou aqui */
+    #[serde(skip_serializing, skip_deserializing)]
+    active_count: usize,
+
+    #[serde(skip_serializing, skip_deserializing)]
+    completed_projects_set: HashSet<String>,
+}
+
+/* Era aquele código 'feio bagarai' do `update_with_user()`.
+ * Mantive o trunc (e não round) pra não mudar as respostas.
+ */
+fn truncate_decimals(value: f32, decimal_digits: i32) -> f32 {
+    let scale_factor = 10f32.powi(decimal_digits);
+    (value * scale_factor).trunc() / scale_factor
+}
+
+impl TeamInsight {
+    fn new() -> Self {
+        TeamInsight {
+            team: String::from(""),
+            total_members: 0,
+            leaders: 0,
+            completed_projects: 0,
+            active_percentage: 0.0,
+            active_count: 0,
+            completed_projects_set: HashSet::new(),
+        }
+    }
+
+    /* Monta o insight final a partir do parcial da representação
+     * colunar (ids e contadores). As Strings só aparecem aqui.
+     */
+    fn from_stats(columns: &ColumnarUsers, team_id: u32, stats: TeamStats) -> Self {
+        let completed_projects_set: HashSet<String> = stats
+            .completed_projects
+            .into_iter()
+            .map(|p| columns.project_dict.get(p).to_owned())
+            .collect();
+
+        TeamInsight {
+            team: columns.team_dict.get(team_id).to_owned(),
+            total_members: stats.total_members,
+            leaders: stats.leaders,
+            completed_projects: completed_projects_set.len(),
+            active_percentage: truncate_decimals(
+                stats.active_count as f32 / stats.total_members as f32 * 100.0,
+                1,
+            ),
+            active_count: stats.active_count,
+            completed_projects_set,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TeamInsightsResp {
+    timestamp: String,
+    #[serde(serialize_with = "negotiation::serialize_millis")]
+    execution_time_ms: u128,
+    teams: Vec<TeamInsight>,
+}
+
+fn build_team_insights(columns: &ColumnarUsers, parallelism: &Parallelism) -> Vec<TeamInsight> {
+    let mut teams: Vec<TeamInsight> = parallelism
+        .map_reduce(
+            columns.len(),
+            |users| columns.team_stats(users),
+            merge_team_stats,
+        )
+        .into_iter()
+        .enumerate()
+        .filter(|(_, stats)| stats.total_members > 0)
+        .map(|(id, stats)| TeamInsight::from_stats(columns, id as u32, stats))
+        .collect();
+
+    teams.sort_by(|a, b| a.team.cmp(&b.team));
+
+    teams
+}
+
+#[get("/team-insights")]
+fn get_team_insights(
+    format: ResponseFormat,
+    root: &State<Root>,
+    parallelism: &State<Parallelism>,
+) -> Negotiated<TeamInsightsResp> {
+    // Agrupa por team.name.
+    // Retorna: total de membros, líderes, projetos
+    // concluídos e % de membros ativos.
+    let start_time = Instant::now();
+
+    let dataset = root.snapshot();
+
+    let teams = dataset.aggregates(parallelism).teams.clone();
+
+    Negotiated(
+        TeamInsightsResp {
+            timestamp: format!("{:?}", Local::now()),
+            execution_time_ms: start_time.elapsed().as_millis(),
+            teams,
+        },
+        format,
+    )
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct ActiveUserLogin {
+    date: String,
+    total: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ActiveUsersResp {
+    timestamp: String,
+    #[serde(serialize_with = "negotiation::serialize_millis")]
+    execution_time_ms: u128,
+    logins: Vec<ActiveUserLogin>,
+}
+
+fn count_logins_per_day(
+    columns: &ColumnarUsers,
+    parallelism: &Parallelism,
+) -> Vec<ActiveUserLogin> {
+    /* Será que faz sentido usar ActiveUserLogin::new()???
+     * Acho que é preciosismo (vou deixar no TODO com nota
+     * de frescura check)
+     */
+    let mut logins: Vec<ActiveUserLogin> = parallelism
+        .map_reduce(
+            columns.len(),
+            |users| columns.logins_per_day(users),
+            merge_counts,
+        )
+        .into_iter()
+        .enumerate()
+        .filter(|(_, total)| *total > 0)
+        .map(|(id, total)| ActiveUserLogin {
+            date: columns.date_dict.get(id as u32).to_owned(),
+            total,
+        })
+        .collect();
+
+    logins.sort_by(|a, b| a.date.cmp(&b.date));
+
+    logins
+}
+
+#[get("/active-users-per-day?<min>")]
+fn get_active_users_per_day(
+    min: Option<u16>,
+    format: ResponseFormat,
+    root: &State<Root>,
+    parallelism: &State<Parallelism>,
+) -> Negotiated<ActiveUsersResp> {
+    // Conta quantos logins aconteceram por data.
+    // Query param opcional: ?min=3000 para filtrar dias com pelo menos 3.000 logins.
+    let start_time = Instant::now();
+
+    let dataset = root.snapshot();
+
+    let logins = dataset.active_users(parallelism, min);
+
+    Negotiated(
+        ActiveUsersResp {
+            timestamp: format!("{:?}", Local::now()),
+            execution_time_ms: start_time.elapsed().as_millis(),
+            logins,
+        },
+        format,
+    )
+}
+
+pub fn rocket() -> Rocket<Build> {
+    rocket::build()
+        .attach(AdHoc::config::<AppConfig>())
+        .attach(root_fairing())
+        .attach(countries::registry_fairing())
+        .attach(regions::region_map_fairing())
+        .attach(parallel::parallelism_fairing())
+        .attach(scenarios::scenarios_fairing())
+        .attach(history::history_fairing())
+        .manage(load_test::LoadTestSlot::default())
+        .mount(
+            "/",
+            routes![
+                index,
+                post_users,
+                get_superusers,
+                get_topcountries,
+                get_team_insights,
+                get_active_users_per_day,
+                evaluation::get_evaluation,
+                evaluation::post_evaluation,
+                history::get_evaluation_history,
+                load_test::get_load_test,
+                leaderboard::get_leaderboard,
+                diff::get_users_diff,
+                export::get_users_export,
+                quality::get_data_quality,
+                columnar::get_memory,
+            ],
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{any::type_name, fs::File, io::Read, path::Path};
+
+    use super::*;
+
+    fn type_of<T>(_: T) -> &'static str {
+        type_name::<T>()
+    }
+
+    pub(crate) fn _load_sample(sample_name: &str) -> String {
+        let formatted_path = format!("./samples/{}.json", sample_name);
+        let sample_path = Path::new(&formatted_path);
+
+        let mut buf = String::new();
+
+        File::open(sample_path)
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+
+        buf
+    }
+
+    pub(crate) fn _load_fixture_users(fixture_name: &str) -> serde_json::Result<Vec<User>> {
+        let buf = _load_sample(fixture_name);
+
+        serde_json::from_str(&buf)
+    }
+
+    pub(crate) fn _build_app_with_empty_root() -> Rocket<Build> {
+        rocket::build()
+            .manage(Root::new())
+            .manage(config::AppConfig::default())
+            .manage(CountryRegistry::builtin())
+            .manage(RegionMap::from_config(&Default::default()).unwrap())
+            .manage(Parallelism::from_config(&Default::default()).unwrap())
+    }
+
+    // Simula o upload: os países já chegam normalizados no Root
+    pub(crate) fn _build_app_with_fixture(fixture_name: &str) -> Rocket<Build> {
+        let mut users = _load_fixture_users(fixture_name).unwrap();
+        let countries = CountryRegistry::builtin();
+        countries.normalize(&mut users);
+
+        rocket::build()
+            .manage(Root::from_users(users))
+            .manage(config::AppConfig::default())
+            .manage(countries)
+            .manage(RegionMap::from_config(&Default::default()).unwrap())
+            .manage(Parallelism::from_config(&Default::default()).unwrap())
+    }
+
+    pub(crate) fn _use_root_state(rocket: &Rocket<Build>) -> &State<Root> {
+        State::get(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_post_users() {
+        let rocket = _build_app_with_empty_root();
+        let root = _use_root_state(&rocket);
+        let buf = _load_sample("usuarios_10");
+
+        let countries = State::get(&rocket).unwrap();
+        let config = State::get(&rocket).unwrap();
+        let parallelism = State::get(&rocket).unwrap();
+
+        let upload = UsersUpload::Json(buf);
+
+        let resp = post_users(upload, root, countries, config, parallelism).unwrap();
+
+        assert_eq!(
+            resp.0,
+            CreateUsersResp {
+                message: "Arquivo recebido com sucesso".to_owned(),
+                user_count: 10,
+                unmapped_countries: vec![],
+            }
+        );
+
+        let users = root.get_users();
+        assert_eq!(users.len(), 10);
+        assert!(users.iter().all(|u| u.country_code.is_some()));
+    }
+
+    #[test]
+    fn test_aggregates_cached_per_version() {
+        let root = Root::from_users(_load_fixture_users("usuarios_10").unwrap());
+        let parallelism = Parallelism::from_config(&Default::default()).unwrap();
+
+        let first = root.snapshot();
+        assert!(std::ptr::eq(
+            first.aggregates(&parallelism),
+            first.aggregates(&parallelism)
+        ));
+        assert_eq!(first.aggregates(&parallelism).teams.len(), 3);
+
+        let mut users = _load_fixture_users("usuarios_10").unwrap();
+        users.truncate(1);
+        root.update(users);
+
+        let second = root.snapshot();
+        assert_eq!(second.aggregates(&parallelism).teams.len(), 1);
+        assert_eq!(first.aggregates(&parallelism).teams.len(), 3);
+    }
+
+    #[test]
+    fn test_parallel_aggregates_match_sequential() {
+        let users = _load_fixture_users("usuarios_10").unwrap();
+        let columns = ColumnarUsers::from_users(&users, &mut SymbolTable::default());
+
+        let sequential = Parallelism::from_config(&config::ParallelConfig {
+            min_users: usize::MAX,
+            threads: 1,
+        })
+        .unwrap();
+        // min_users = 0 força o map/reduce mesmo com 10 usuários
+        let parallel = Parallelism::from_config(&config::ParallelConfig {
+            min_users: 0,
+            threads: 4,
+        })
+        .unwrap();
+
+        let as_json = |p: &Parallelism| {
+            serde_json::json!({
+                "countries": count_countries(&columns, p),
+                "teams": build_team_insights(&columns, p),
+                "logins": count_logins_per_day(&columns, p),
+            })
+        };
+
+        assert_eq!(as_json(&sequential), as_json(&parallel));
+    }
+
+    #[test]
+    fn test_get_superusers() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+
+        let resp = get_superusers(ResponseFormat::Json, state)
+            .right()
+            .unwrap()
+            .0;
+
+        let expect_user = r#"
+            {
+                "id": "c460b871-77ec-46f1-9127-22ea6989b0bc",
+                "name": "Clarice Porto",
+                "age": 52,
+                "score": 1040,
+                "active": true,
+                "country": "Argentina",
+                "country_code": "AR",
+                "team": {
+                    "name": "Frontend Avengers",
+                    "leader": true,
+                    "projects": [
+                        {
+                            "name": "Sistema Interno",
+                            "completed": true
+                        }
+                    ]
+                },
+                "logs": [
+                    {
+                        "date": "2025-03-28",
+                        "action": "login"
+                    },
+                    {
+                        "date": "2025-03-29",
+                        "action": "login"
+                    },
+                    {
+                        "date": "2025-03-30",
+                        "action": "login"
+                    },
+                    {
+                        "date": "2025-03-27",
+                        "action": "login"
+                    },
+                    {
+                        "date": "2025-03-30",
+                        "action": "login"
+                    }
+                ]
+            }
+        "#;
+
+        assert_eq!(type_of(resp.timestamp), "alloc::string::String");
+        assert_eq!(type_of(resp.execution_time_ms), "u128");
+        assert_eq!(resp.user_count, 1);
+        assert_eq!(resp.data[0], serde_json::from_str(expect_user).unwrap());
+    }
+
+    #[test]
+    fn test_get_topcountries() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let region_map = State::get(&rocket).unwrap();
+        let resp = get_topcountries(
+            None,
+            ResponseFormat::Json,
+            state,
+            region_map,
+            State::get(&rocket).unwrap(),
+        )
+        .0;
+
+        assert_eq!(
+            resp.countries,
+            vec![
+                CountrySummary {
+                    country: "Argentina".into(),
+                    code: Some("AR".into()),
+                    total: 3
+                },
+                CountrySummary {
+                    country: "Canadá".into(),
+                    code: Some("CA".into()),
+                    total: 2
+                },
+                CountrySummary {
+                    country: "Japão".into(),
+                    code: Some("JP".into()),
+                    total: 2
+                },
+                CountrySummary {
+                    country: "Brasil".into(),
+                    code: Some("BR".into()),
+                    total: 1
+                },
+                CountrySummary {
+                    country: "França".into(),
+                    code: Some("FR".into()),
+                    total: 1
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn test_get_topcountries_by_continent() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let region_map = State::get(&rocket).unwrap();
+        let resp = get_topcountries(
+            Some(CountryGrouping::Continent),
+            ResponseFormat::Json,
+            state,
+            region_map,
+            State::get(&rocket).unwrap(),
+        )
+        .0;
+
+        assert_eq!(resp.countries.len(), 5);
+        assert_eq!(
+            serde_json::to_value(&resp.regions).unwrap()[0],
+            serde_json::json!({
+                "region": "América do Sul",
+                "total": 4,
+                "countries": [
+                    { "country": "Argentina", "code": "AR", "total": 3 },
+                    { "country": "Brasil", "code": "BR", "total": 1 },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_team_insights() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let resp = get_team_insights(ResponseFormat::Json, state, State::get(&rocket).unwrap()).0;
+
+        assert_eq!(
+            resp.teams,
+            vec![
+                TeamInsight {
+                    team: "Frontend Avengers".into(),
+                    total_members: 4,
+                    leaders: 1,
+                    completed_projects: 4,
+                    active_percentage: 100.0,
+                    active_count: 4,
+                    completed_projects_set: vec![
+                        "API Pública",
+                        "Sistema Interno",
+                        "Dashboard",
+                        "Landing Page"
+                    ]
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<HashSet<String>>()
+                },
+                TeamInsight {
+                    team: "Fullstack Force".into(),
+                    total_members: 4,
+                    leaders: 1,
+                    completed_projects: 4,
+                    active_percentage: 100.0,
+                    active_count: 4,
+                    completed_projects_set: vec![
+                        "Landing Page",
+                        "Sistema Interno",
+                        "Dashboard",
+                        "Mobile App"
+                    ]
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<HashSet<String>>()
+                },
+                TeamInsight {
+                    team: "UX Wizards".into(),
+                    total_members: 2,
+                    leaders: 0,
+                    completed_projects: 1,
+                    active_percentage: 100.0,
+                    active_count: 2,
+                    completed_projects_set: vec!["Dashboard"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect::<HashSet<String>>()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_active_users_per_day() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let state = _use_root_state(&rocket);
+        let resp = get_active_users_per_day(
+            Option::None,
+            ResponseFormat::Json,
+            state,
+            State::get(&rocket).unwrap(),
+        )
+        .0;
+
+        assert_eq!(
+            resp.logins,
+            vec![
+                ActiveUserLogin {
+                    date: "2025-03-25".into(),
+                    total: 6
+                },
+                ActiveUserLogin {
+                    date: "2025-03-26".into(),
+                    total: 6
+                },
+                ActiveUserLogin {
+                    date: "2025-03-27".into(),
+                    total: 4
+                },
+                ActiveUserLogin {
+                    date: "2025-03-28".into(),
+                    total: 4
+                },
+                ActiveUserLogin {
+                    date: "2025-03-29".into(),
+                    total: 5
+                },
+                ActiveUserLogin {
+                    date: "2025-03-30".into(),
+                    total: 5
+                },
+                ActiveUserLogin {
+                    date: "2025-03-31".into(),
+                    total: 6
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,489 @@
+use rocket::State;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::tokio::sync::Mutex;
+use rocket::tokio::task::JoinSet;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::config::{AppConfig, LoadTestConfig};
+use crate::evaluation::{base_url_from_config, parse_local_target};
+use crate::latency::REQUEST_TIMEOUT;
+use crate::scenarios::Scenarios;
+
+/* Modo "carga" do avaliador.
+ *
+ * O `/evaluation` faz um request por endpoint, o que não diz nada
+ * sobre a meta do README (1MM de requests/dia abaixo de 200 ms).
+ * Aqui cada endpoint leva uma rajada com N workers em paralelo, por
+ * um tempo ou até um total de requests, depois de um aquecimento que
+ * não entra na conta.
+ *
+ * Os endpoints (os mesmos cenários do `/evaluation`) são testados um
+ * de cada vez pra um não roubar CPU do outro. Ao contrário do
+ * `latency.rs`, o Client é compartilhado e reaproveita as conexões
+ * (keep-alive), como faria um cliente real.
+ */
+
+/* Uma carga por vez: duas rodando juntas se atrapalham nos números, e
+ * vários GETs em paralelo multiplicariam o tráfego gerado.
+ */
+#[derive(Default)]
+pub struct LoadTestSlot(Mutex<()>);
+
+#[derive(FromForm, Debug, Default)]
+pub struct LoadParams {
+    concurrency: Option<usize>,
+    // Segundos medindo cada endpoint
+    duration: Option<u64>,
+    // Total de requests por endpoint (ganha do `duration`)
+    requests: Option<usize>,
+    warmup: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Stop {
+    After(Duration),
+    Requests(usize),
+}
+
+#[derive(Debug)]
+struct LoadPlan {
+    concurrency: usize,
+    warmup: usize,
+    stop: Stop,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct LatencyPercentiles {
+    p50_us: u64,
+    p90_us: u64,
+    p99_us: u64,
+    max_us: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct LoadMetric {
+    requests: usize,
+    errors: usize,
+    error_rate: f64,
+    throughput_rps: f64,
+    elapsed_ms: u128,
+    // Só dos requests que deram certo
+    latency: LatencyPercentiles,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LoadTestResp {
+    target: String,
+    concurrency: usize,
+    warmup_requests: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requests_per_endpoint: Option<usize>,
+    endpoints: BTreeMap<String, LoadMetric>,
+}
+
+struct Sample {
+    latency_us: u64,
+    ok: bool,
+}
+
+impl LoadPlan {
+    fn from_params(params: &LoadParams, config: &LoadTestConfig) -> Result<Self, String> {
+        let concurrency = params.concurrency.unwrap_or(config.concurrency);
+
+        if concurrency == 0 || concurrency > config.max_concurrency {
+            return Err(format!(
+                "concurrency must be between 1 and {}",
+                config.max_concurrency
+            ));
+        }
+
+        let stop = match (params.requests, params.duration) {
+            (Some(0), _) => return Err(String::from("requests must be greater than 0")),
+            (Some(requests), _) if requests > config.max_requests => {
+                return Err(format!("requests must be at most {}", config.max_requests));
+            }
+            (Some(requests), _) => Stop::Requests(requests),
+            (None, Some(0)) => return Err(String::from("duration must be greater than 0")),
+            (None, duration) => {
+                let duration = duration.unwrap_or(config.duration_secs);
+
+                if duration > config.max_duration_secs {
+                    return Err(format!(
+                        "duration must be at most {} seconds",
+                        config.max_duration_secs
+                    ));
+                }
+
+                Stop::After(Duration::from_secs(duration))
+            }
+        };
+
+        let warmup = params.warmup.unwrap_or(config.warmup_requests);
+
+        if warmup > config.max_warmup_requests {
+            return Err(format!(
+                "warmup must be at most {}",
+                config.max_warmup_requests
+            ));
+        }
+
+        Ok(LoadPlan {
+            concurrency,
+            warmup,
+            stop,
+        })
+    }
+}
+
+async fn run_workers(
+    client: &reqwest::Client,
+    url: &str,
+    expected_status: u16,
+    concurrency: usize,
+    stop: Stop,
+) -> Vec<Sample> {
+    let (deadline, limit) = match stop {
+        // O `from_params` já limita a duração; se mesmo assim estourar,
+        // não dispara nada em vez de rodar pra sempre
+        Stop::After(duration) => match Instant::now().checked_add(duration) {
+            Some(deadline) => (Some(deadline), usize::MAX),
+            None => (None, 0),
+        },
+        Stop::Requests(requests) => (None, requests),
+    };
+
+    // Contador compartilhado: cada worker "pega uma senha" antes de disparar
+    let issued = Arc::new(AtomicUsize::new(0));
+    let mut workers = JoinSet::new();
+
+    for _ in 0..concurrency {
+        let client = client.clone();
+        let url = url.to_owned();
+        let issued = issued.clone();
+
+        workers.spawn(async move {
+            let mut samples = Vec::new();
+
+            loop {
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    break;
+                }
+                if issued.fetch_add(1, Ordering::Relaxed) >= limit {
+                    break;
+                }
+
+                let start = Instant::now();
+                let ok = match client.get(&url).send().await {
+                    Ok(resp) => {
+                        resp.status().as_u16() == expected_status && resp.bytes().await.is_ok()
+                    }
+                    Err(_) => false,
+                };
+
+                samples.push(Sample {
+                    latency_us: start.elapsed().as_micros() as u64,
+                    ok,
+                });
+            }
+
+            samples
+        });
+    }
+
+    let mut samples = Vec::new();
+    while let Some(worker) = workers.join_next().await {
+        if let Ok(worker_samples) = worker {
+            samples.extend(worker_samples);
+        }
+    }
+
+    samples
+}
+
+// Nearest-rank: o menor valor que cobre p% das amostras
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn summarize(samples: Vec<Sample>, elapsed: Duration) -> LoadMetric {
+    let requests = samples.len();
+
+    let mut latencies: Vec<u64> = samples
+        .iter()
+        .filter(|s| s.ok)
+        .map(|s| s.latency_us)
+        .collect();
+    latencies.sort_unstable();
+
+    let errors = requests - latencies.len();
+    let secs = elapsed.as_secs_f64();
+
+    LoadMetric {
+        requests,
+        errors,
+        error_rate: if requests > 0 {
+            errors as f64 / requests as f64
+        } else {
+            0.0
+        },
+        throughput_rps: if secs > 0.0 {
+            requests as f64 / secs
+        } else {
+            0.0
+        },
+        elapsed_ms: elapsed.as_millis(),
+        latency: LatencyPercentiles {
+            p50_us: percentile(&latencies, 50.0),
+            p90_us: percentile(&latencies, 90.0),
+            p99_us: percentile(&latencies, 99.0),
+            max_us: latencies.last().copied().unwrap_or(0),
+        },
+    }
+}
+
+async fn load_endpoint(
+    client: &reqwest::Client,
+    url: &str,
+    expected_status: u16,
+    plan: &LoadPlan,
+) -> LoadMetric {
+    if plan.warmup > 0 {
+        let warmup = Stop::Requests(plan.warmup);
+        run_workers(client, url, expected_status, plan.concurrency, warmup).await;
+    }
+
+    let start = Instant::now();
+    let samples = run_workers(client, url, expected_status, plan.concurrency, plan.stop).await;
+
+    summarize(samples, start.elapsed())
+}
+
+#[get("/evaluation/load?<target>&<params..>")]
+pub async fn get_load_test(
+    target: Option<&str>,
+    params: LoadParams,
+    rocket_config: &rocket::Config,
+    config: &State<AppConfig>,
+    scenarios: &State<Scenarios>,
+    slot: &State<LoadTestSlot>,
+) -> Result<Json<LoadTestResp>, Custom<String>> {
+    // Ex: /evaluation/load?concurrency=32&duration=30&warmup=100
+    //     /evaluation/load?requests=10000
+    // `?target=` segue a mesma regra do `/evaluation`: loopback ou
+    // `allowed_targets`. Com outra carga rodando => 409.
+    let bad_request = |e: String| Custom(Status::BadRequest, e);
+
+    let plan = LoadPlan::from_params(&params, &config.load_test).map_err(bad_request)?;
+
+    let base_url = match target {
+        Some(target) => {
+            parse_local_target(target, &config.evaluation.allowed_targets).map_err(bad_request)?
+        }
+        None => base_url_from_config(rocket_config),
+    };
+
+    let Ok(_running) = slot.0.try_lock() else {
+        return Err(Custom(
+            Status::Conflict,
+            String::from("a load test is already running"),
+        ));
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| Custom(Status::InternalServerError, e.to_string()))?;
+
+    let mut endpoints = BTreeMap::new();
+
+    for scenario in scenarios.0.iter() {
+        let url = scenario.url(&base_url);
+        let metric = load_endpoint(&client, &url, scenario.expected_status, &plan).await;
+
+        endpoints.insert(scenario.name(), metric);
+    }
+
+    let (duration_secs, requests_per_endpoint) = match plan.stop {
+        Stop::After(duration) => (Some(duration.as_secs()), None),
+        Stop::Requests(requests) => (None, Some(requests)),
+    };
+
+    Ok(Json(LoadTestResp {
+        target: base_url,
+        concurrency: plan.concurrency,
+        warmup_requests: plan.warmup,
+        duration_secs,
+        requests_per_endpoint,
+        endpoints,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpListener;
+
+    // Servidor HTTP de mentirinha: responde `{}` e fecha a conexão
+    async fn _serve_empty_json() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        rocket::tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                rocket::tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let _ = socket
+                        .write_all(
+                            b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+                        )
+                        .await;
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<u64> = (1..=100).collect();
+
+        assert_eq!(percentile(&sorted, 50.0), 50);
+        assert_eq!(percentile(&sorted, 90.0), 90);
+        assert_eq!(percentile(&sorted, 99.0), 99);
+        assert_eq!(percentile(&[7], 99.0), 7);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn test_load_plan() {
+        let config = LoadTestConfig::default();
+
+        let plan = LoadPlan::from_params(&LoadParams::default(), &config).unwrap();
+        assert_eq!(plan.concurrency, config.concurrency);
+        assert_eq!(
+            plan.stop,
+            Stop::After(Duration::from_secs(config.duration_secs))
+        );
+
+        let params = LoadParams {
+            requests: Some(500),
+            duration: Some(5),
+            ..Default::default()
+        };
+        let plan = LoadPlan::from_params(&params, &config).unwrap();
+        assert_eq!(plan.stop, Stop::Requests(500));
+
+        let too_many = LoadParams {
+            concurrency: Some(config.max_concurrency + 1),
+            ..Default::default()
+        };
+        assert!(LoadPlan::from_params(&too_many, &config).is_err());
+    }
+
+    #[test]
+    fn test_load_plan_limits() {
+        let config = LoadTestConfig::default();
+
+        let rejected = [
+            LoadParams {
+                duration: Some(u64::MAX),
+                ..Default::default()
+            },
+            LoadParams {
+                duration: Some(config.max_duration_secs + 1),
+                ..Default::default()
+            },
+            LoadParams {
+                requests: Some(config.max_requests + 1),
+                ..Default::default()
+            },
+            LoadParams {
+                warmup: Some(config.max_warmup_requests + 1),
+                ..Default::default()
+            },
+        ];
+
+        for params in rejected.iter() {
+            assert!(
+                LoadPlan::from_params(params, &config).is_err(),
+                "{:?}",
+                params
+            );
+        }
+
+        // No limite ainda vale
+        let params = LoadParams {
+            duration: Some(config.max_duration_secs),
+            warmup: Some(config.max_warmup_requests),
+            ..Default::default()
+        };
+        assert!(LoadPlan::from_params(&params, &config).is_ok());
+
+        // Um default do Rocket.toml acima do teto também é recusado
+        let config = LoadTestConfig {
+            duration_secs: config.max_duration_secs + 1,
+            ..config
+        };
+        assert!(LoadPlan::from_params(&LoadParams::default(), &config).is_err());
+    }
+
+    #[rocket::async_test]
+    async fn test_load_endpoint() {
+        let base_url = _serve_empty_json().await;
+        let client = reqwest::Client::new();
+        let plan = LoadPlan {
+            concurrency: 4,
+            warmup: 2,
+            stop: Stop::Requests(40),
+        };
+
+        let url = format!("{}/superusers", base_url);
+        let metric = load_endpoint(&client, &url, 200, &plan).await;
+
+        assert_eq!(metric.requests, 40);
+        assert_eq!(metric.errors, 0);
+        assert!(metric.latency.p50_us <= metric.latency.p99_us);
+        assert!(metric.latency.p99_us <= metric.latency.max_us);
+        assert!(metric.throughput_rps > 0.0);
+    }
+
+    #[test]
+    fn test_get_load_test_guards() {
+        let rocket = crate::tests::_build_app_with_empty_root()
+            .manage(Scenarios::builtin())
+            .manage(LoadTestSlot::default())
+            .mount("/", routes![get_load_test]);
+        let client = rocket::local::blocking::Client::untracked(rocket).unwrap();
+
+        let resp = client
+            .get("/evaluation/load?target=http://10.0.0.2:8000&requests=1")
+            .dispatch();
+        assert_eq!(resp.status(), Status::BadRequest);
+
+        // Com uma carga "rodando", a próxima leva 409
+        let slot = client.rocket().state::<LoadTestSlot>().unwrap();
+        let running = slot.0.try_lock().unwrap();
+
+        let resp = client
+            .get("/evaluation/load?target=localhost:1&requests=1")
+            .dispatch();
+        assert_eq!(resp.status(), Status::Conflict);
+        drop(running);
+    }
+}
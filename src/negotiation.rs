@@ -0,0 +1,318 @@
+use rocket::http::{ContentType, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer};
+
+use crate::report::Tabular;
+
+/* Formato da resposta dos endpoints de análise.
+ *
+ * JSON continua sendo o padrão. Pra colar numa planilha, dá pra pedir
+ * CSV com `?format=csv` ou `Accept: text/csv` - o `?format=` ganha do
+ * header. As colunas são as mesmas do `challengeresult report`
+ * (veja `report.rs`), então o CSV da API e o da CLI batem.
+ *
+ * NDJSON (`Accept: application/x-ndjson` ou `?format=ndjson`) só faz
+ * sentido no `/superusers`, que manda um usuário por linha conforme
+ * vai filtrando. Nos outros endpoints cai no JSON normal.
+ *
+ * MessagePack e CBOR (`?format=msgpack|cbor`) são o mesmo JSON, só que
+ * binário - serializar o `/superusers` grande fica bem mais rápido.
+ */
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum ResponseFormat {
+    Json,
+    Csv,
+    Ndjson,
+    MsgPack,
+    Cbor,
+}
+
+impl ResponseFormat {
+    fn from_accept(request: &Request<'_>) -> Self {
+        let Some(accept) = request.accept() else {
+            return ResponseFormat::Json;
+        };
+
+        let media = accept.preferred().media_type();
+
+        match (media.top().as_str(), media.sub().as_str()) {
+            ("text", "csv") => ResponseFormat::Csv,
+            ("application", "x-ndjson") => ResponseFormat::Ndjson,
+            ("application", "msgpack" | "x-msgpack") => ResponseFormat::MsgPack,
+            ("application", "cbor") => ResponseFormat::Cbor,
+            _ => ResponseFormat::Json,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ResponseFormat {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.query_value::<ResponseFormat>("format") {
+            Some(Err(_)) => Outcome::Error((Status::BadRequest, ())),
+            Some(Ok(format)) => Outcome::Success(format),
+            None => Outcome::Success(ResponseFormat::from_accept(request)),
+        }
+    }
+}
+
+/* Os formatos binários. Também valem pro upload: o `POST /users` aceita
+ * o export em MessagePack ou CBOR pelo `Content-Type`.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryFormat {
+    MsgPack,
+    Cbor,
+}
+
+impl BinaryFormat {
+    pub fn content_type(self) -> ContentType {
+        match self {
+            BinaryFormat::MsgPack => ContentType::MsgPack,
+            BinaryFormat::Cbor => ContentType::new("application", "cbor"),
+        }
+    }
+
+    pub fn from_content_type(content_type: &ContentType) -> Option<Self> {
+        match (content_type.top().as_str(), content_type.sub().as_str()) {
+            ("application", "msgpack" | "x-msgpack") => Some(BinaryFormat::MsgPack),
+            ("application", "cbor") => Some(BinaryFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    // `to_vec_named`: structs viram mapas, com os mesmos nomes do JSON
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            BinaryFormat::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            BinaryFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            BinaryFormat::MsgPack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            BinaryFormat::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/* O MessagePack manda u128 como 16 bytes de binário, e ninguém do outro
+ * lado quer decodificar isso. Os tempos em ms cabem num u64 com folga.
+ */
+pub fn serialize_millis<S: Serializer>(millis: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(u64::try_from(*millis).unwrap_or(u64::MAX))
+}
+
+pub fn serialize_millis_opt<S: Serializer>(
+    millis: &Option<u128>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match millis {
+        Some(millis) => serialize_millis(millis, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug)]
+pub struct Binary<T>(pub T, pub BinaryFormat);
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Binary<T> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let bytes = self.1.encode(&self.0).map_err(|e| {
+            log::error!("failed to serialize {:?}: {}", self.1, e);
+            Status::InternalServerError
+        })?;
+
+        (self.1.content_type(), bytes).respond_to(request)
+    }
+}
+
+/* A resposta do handler + o formato pedido. O `.0` continua sendo a
+ * struct da resposta, então quem chama o handler direto (testes,
+ * bench) não muda nada.
+ */
+#[derive(Debug)]
+pub struct Negotiated<T>(pub T, pub ResponseFormat);
+
+impl<'r, T: Serialize + Tabular> Responder<'r, 'static> for Negotiated<T> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        match self.1 {
+            ResponseFormat::Json | ResponseFormat::Ndjson => Json(self.0).respond_to(request),
+            // `text/csv; charset=utf-8`: sem isso os acentos viram lixo
+            ResponseFormat::Csv => (ContentType::CSV, self.0.table().to_csv()).respond_to(request),
+            ResponseFormat::MsgPack => Binary(self.0, BinaryFormat::MsgPack).respond_to(request),
+            ResponseFormat::Cbor => Binary(self.0, BinaryFormat::Cbor).respond_to(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::{Accept, ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use super::BinaryFormat;
+    use crate::tests::{_build_app_with_empty_root, _build_app_with_fixture, _load_fixture_users};
+    use crate::{GetSuperusersResp, TeamInsightsResp};
+
+    fn _client() -> Client {
+        let rocket = _build_app_with_fixture("usuarios_10").mount(
+            "/",
+            routes![
+                crate::get_superusers,
+                crate::get_topcountries,
+                crate::get_team_insights,
+                crate::get_active_users_per_day,
+            ],
+        );
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_csv_negotiation() {
+        let client = _client();
+
+        let resp = client.get("/top-countries?format=csv").dispatch();
+        assert_eq!(resp.content_type(), Some(ContentType::CSV));
+        let csv = resp.into_string().unwrap();
+        assert!(csv.starts_with("country,code,total\r\nArgentina,AR,3\r\n"));
+        assert!(csv.contains("Canadá,CA,2"));
+
+        let resp = client.get("/superusers").header(Accept::CSV).dispatch();
+        assert_eq!(resp.content_type(), Some(ContentType::CSV));
+        assert!(resp.into_string().unwrap().starts_with(
+            "id,name,age,score,active,country,country_code,team,leader,projects,completed_projects,logs\r\n"
+        ));
+
+        let resp = client
+            .get("/active-users-per-day?min=3&format=csv")
+            .dispatch();
+        assert!(resp.into_string().unwrap().starts_with("date,total\r\n"));
+
+        let resp = client
+            .get("/top-countries?group=continent&format=csv")
+            .dispatch();
+        assert!(
+            resp.into_string()
+                .unwrap()
+                .starts_with("region,region_total,country,code,total\r\n")
+        );
+
+        // `?format=` ganha do Accept; sem nenhum dos dois continua JSON
+        let resp = client
+            .get("/team-insights?format=json")
+            .header(Accept::CSV)
+            .dispatch();
+        assert_eq!(resp.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            client.get("/team-insights").dispatch().content_type(),
+            Some(ContentType::JSON)
+        );
+
+        let resp = client.get("/team-insights?format=xls").dispatch();
+        assert_eq!(resp.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_ndjson_superusers() {
+        let client = _client();
+        let ndjson = ContentType::new("application", "x-ndjson");
+
+        let expected = client
+            .get("/superusers")
+            .dispatch()
+            .into_json::<crate::GetSuperusersResp>()
+            .unwrap();
+
+        let resp = client
+            .get("/superusers")
+            .header(Accept::from(ndjson.media_type().clone()))
+            .dispatch();
+        assert_eq!(resp.content_type(), Some(ndjson));
+
+        let body = resp.into_string().unwrap();
+        let lines: Vec<serde_json::Value> = body
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        // Um usuário por linha, na ordem do JSON, e o resumo no final
+        let (summary, users) = lines.split_last().unwrap();
+        let ids: Vec<&str> = users.iter().map(|u| u["id"].as_str().unwrap()).collect();
+        let expected_ids: Vec<&str> = expected.data.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(ids, expected_ids);
+        assert_eq!(summary["summary"]["user_count"], expected.user_count);
+        assert!(summary["summary"]["execution_time_ms"].is_u64());
+
+        // `?format=ndjson` dá no mesmo; nos outros endpoints vira JSON
+        let resp = client.get("/superusers?format=ndjson").dispatch();
+        assert_eq!(resp.into_string().unwrap().lines().count(), lines.len());
+
+        let resp = client.get("/team-insights?format=ndjson").dispatch();
+        assert_eq!(resp.content_type(), Some(ContentType::JSON));
+    }
+
+    #[test]
+    fn test_binary_negotiation() {
+        let client = _client();
+        let cbor = ContentType::new("application", "cbor");
+
+        let expected: GetSuperusersResp = client.get("/superusers").dispatch().into_json().unwrap();
+
+        let resp = client.get("/superusers?format=msgpack").dispatch();
+        assert_eq!(resp.content_type(), Some(ContentType::MsgPack));
+        let body = resp.into_bytes().unwrap();
+        let decoded: GetSuperusersResp = BinaryFormat::MsgPack.decode(&body).unwrap();
+        assert_eq!(decoded.data, expected.data);
+
+        // Os ms vão como inteiro normal, não como os 16 bytes de um u128
+        let map: std::collections::BTreeMap<String, serde_json::Value> =
+            rmp_serde::from_slice(&body).unwrap();
+        assert!(map["execution_time_ms"].is_u64());
+
+        let resp = client
+            .get("/team-insights")
+            .header(Accept::from(cbor.media_type().clone()))
+            .dispatch();
+        assert_eq!(resp.content_type(), Some(cbor));
+        let decoded: TeamInsightsResp = BinaryFormat::Cbor
+            .decode(&resp.into_bytes().unwrap())
+            .unwrap();
+        assert!(!decoded.teams.is_empty());
+    }
+
+    #[test]
+    fn test_binary_upload() {
+        let rocket = _build_app_with_empty_root().mount("/", routes![crate::post_users]);
+        let client = Client::untracked(rocket).unwrap();
+        let users = _load_fixture_users("usuarios_10").unwrap();
+
+        for format in [BinaryFormat::MsgPack, BinaryFormat::Cbor] {
+            let resp = client
+                .post("/users")
+                .header(format.content_type())
+                .body(format.encode(&users).unwrap())
+                .dispatch();
+            assert_eq!(resp.status(), Status::Ok);
+            assert!(resp.into_string().unwrap().contains("\"user_count\":10"));
+        }
+
+        let resp = client
+            .post("/users")
+            .header(ContentType::MsgPack)
+            .body("não é msgpack")
+            .dispatch();
+        assert_eq!(resp.status(), Status::UnprocessableEntity);
+    }
+}
@@ -0,0 +1,108 @@
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use rocket::fairing::AdHoc;
+use rocket::{Build, Rocket};
+use std::ops::Range;
+
+use crate::config::{AppConfig, ParallelConfig};
+
+/* Map/reduce em pedaços (chunks) usando um pool do rayon.
+ *
+ * Cada chunk é um intervalo de usuários; o `map` gera um parcial
+ * (contadores, `TeamStats`, ...) e o `reduce` junta dois parciais.
+ * Abaixo de `min_users` não vale a pena acordar as threads: roda
+ * tudo de uma vez na thread do request mesmo.
+ *
+ * O pool é próprio (e não o global do rayon) pra não brigar com as
+ * threads de I/O do Rocket/tokio - o tamanho vem do Rocket.toml.
+ */
+pub struct Parallelism {
+    pool: ThreadPool,
+    min_users: usize,
+}
+
+// Chunks por thread: sobra trabalho pra quem terminar antes
+const CHUNKS_PER_THREAD: usize = 4;
+
+impl Parallelism {
+    pub fn from_config(config: &ParallelConfig) -> Result<Self, String> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(config.threads)
+            .thread_name(|i| format!("aggregation-{}", i))
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Parallelism {
+            pool,
+            min_users: config.min_users,
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    pub fn map_reduce<T, M, R>(&self, len: usize, map: M, reduce: R) -> T
+    where
+        T: Send,
+        M: Fn(Range<usize>) -> T + Sync,
+        R: Fn(T, T) -> T + Sync,
+    {
+        if len < self.min_users || self.threads() < 2 {
+            return map(0..len);
+        }
+
+        let chunk_count = self.threads() * CHUNKS_PER_THREAD;
+        let chunk_size = len.div_ceil(chunk_count).max(1);
+
+        self.pool.install(|| {
+            (0..len)
+                .step_by(chunk_size)
+                .collect::<Vec<usize>>()
+                .into_par_iter()
+                .map(|start| map(start..(start + chunk_size).min(len)))
+                .reduce_with(&reduce)
+                .unwrap_or_else(|| map(0..0))
+        })
+    }
+}
+
+pub fn parallelism_fairing() -> AdHoc {
+    AdHoc::try_on_ignite(
+        "Aggregation Thread Pool",
+        |rocket: Rocket<Build>| async move {
+            let config = rocket
+                .state::<AppConfig>()
+                .map(|c| c.parallel.clone())
+                .unwrap_or_default();
+
+            match Parallelism::from_config(&config) {
+                Ok(parallelism) => Ok(rocket.manage(parallelism)),
+                Err(e) => {
+                    log::error!("failed to build the aggregation thread pool: {}", e);
+                    Err(rocket)
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_reduce_chunks() {
+        let parallelism = Parallelism::from_config(&ParallelConfig {
+            min_users: 0,
+            threads: 3,
+        })
+        .unwrap();
+
+        let total = parallelism.map_reduce(1000, |range| range.sum::<usize>(), |a, b| a + b);
+        assert_eq!(total, (0..1000).sum::<usize>());
+
+        let empty = parallelism.map_reduce(0, |range| range.len(), |a, b| a + b);
+        assert_eq!(empty, 0);
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{Local, NaiveDate};
+use rocket::State;
+use rocket::serde::json::Json;
+use rocket::tokio::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::config::{AppConfig, DataQualityConfig};
+use crate::{Root, User};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    DuplicateId,
+    EmptyTeamName,
+    NoLogs,
+    InvalidLogDate,
+    FutureLogDate,
+    AgeOutOfRange,
+    ScoreOutOfRange,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AnomalyReport {
+    kind: AnomalyKind,
+    count: usize,
+    sample_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DataQualityResp {
+    timestamp: String,
+    execution_time_ms: u128,
+    user_count: usize,
+    users_with_anomalies: usize,
+    thresholds: DataQualityConfig,
+    anomalies: Vec<AnomalyReport>,
+}
+
+impl AnomalyReport {
+    fn new(kind: AnomalyKind) -> Self {
+        AnomalyReport {
+            kind,
+            count: 0,
+            sample_ids: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, u: &User, sample_size: usize) {
+        self.count += 1;
+
+        if self.sample_ids.len() < sample_size {
+            self.sample_ids.push(u.id.clone());
+        }
+    }
+}
+
+/* Um usuário pode cair em mais de uma anomalia (ex: sem time e com
+ * score zoado). Cada classe conta separado; o `users_with_anomalies`
+ * conta a pessoa uma vez só.
+ */
+fn scan_users(users: &[User], config: &DataQualityConfig, today: NaiveDate) -> DataQualityResp {
+    let start_time = Instant::now();
+
+    let mut anomalies: Vec<AnomalyReport> = [
+        AnomalyKind::DuplicateId,
+        AnomalyKind::EmptyTeamName,
+        AnomalyKind::NoLogs,
+        AnomalyKind::InvalidLogDate,
+        AnomalyKind::FutureLogDate,
+        AnomalyKind::AgeOutOfRange,
+        AnomalyKind::ScoreOutOfRange,
+    ]
+    .into_iter()
+    .map(AnomalyReport::new)
+    .collect();
+
+    let mut seen_ids: HashSet<&str> = HashSet::with_capacity(users.len());
+    let mut users_with_anomalies = 0;
+
+    for u in users {
+        let log_dates: Vec<Option<NaiveDate>> = u
+            .logs
+            .iter()
+            .map(|l| NaiveDate::parse_from_str(&l.date, "%Y-%m-%d").ok())
+            .collect();
+
+        let checks = [
+            !seen_ids.insert(u.id.as_str()),
+            u.team.name.trim().is_empty(),
+            u.logs.is_empty(),
+            log_dates.iter().any(Option::is_none),
+            log_dates.iter().flatten().any(|d| *d > today),
+            u.age < config.min_age || u.age > config.max_age,
+            u.score < config.min_score || u.score > config.max_score,
+        ];
+
+        let mut has_anomaly = false;
+
+        for (report, failed) in anomalies.iter_mut().zip(checks) {
+            if failed {
+                report.record(u, config.sample_size);
+                has_anomaly = true;
+            }
+        }
+
+        if has_anomaly {
+            users_with_anomalies += 1;
+        }
+    }
+
+    DataQualityResp {
+        timestamp: format!("{:?}", Local::now()),
+        execution_time_ms: start_time.elapsed().as_millis(),
+        user_count: users.len(),
+        users_with_anomalies,
+        thresholds: config.clone(),
+        anomalies,
+    }
+}
+
+#[get("/data-quality")]
+pub fn get_data_quality(root: &State<Root>, config: &State<AppConfig>) -> Json<DataQualityResp> {
+    // Varre o dataset atual atrás de dados esquisitos do export.
+    // Os limites (idade, score, amostras) vêm da seção
+    // `[default.data_quality]` do Rocket.toml.
+    let dataset = root.snapshot();
+
+    Json(scan_users(
+        &dataset.users,
+        &config.data_quality,
+        Local::now().date_naive(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::Symbol;
+    use crate::tests::{_build_app_with_fixture, _load_fixture_users, _use_root_state};
+
+    fn _counts(resp: &DataQualityResp) -> Vec<(AnomalyKind, usize)> {
+        resp.anomalies.iter().map(|a| (a.kind, a.count)).collect()
+    }
+
+    #[test]
+    fn test_get_data_quality() {
+        let rocket = _build_app_with_fixture("usuarios_10");
+        let root = _use_root_state(&rocket);
+        let config = State::get(&rocket).unwrap();
+
+        let resp = get_data_quality(root, config).0;
+
+        assert_eq!(resp.user_count, 10);
+        assert_eq!(resp.users_with_anomalies, 0);
+        assert!(resp.anomalies.iter().all(|a| a.count == 0));
+    }
+
+    #[test]
+    fn test_scan_users_anomalies() {
+        let mut users = _load_fixture_users("usuarios_10").unwrap();
+
+        users[0].team.name = Symbol::intern("  ");
+        users[1].logs.clear();
+        users[2].logs[0].date = Symbol::intern("31/03/2025");
+        users[3].logs[0].date = Symbol::intern("2025-04-02");
+        users[4].age = 255;
+        users[5].score = 5000;
+        users[6].id = users[7].id.clone();
+
+        let config = DataQualityConfig {
+            sample_size: 1,
+            ..DataQualityConfig::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 4, 1).unwrap();
+
+        let resp = scan_users(&users, &config, today);
+
+        assert_eq!(
+            _counts(&resp),
+            vec![
+                (AnomalyKind::DuplicateId, 1),
+                (AnomalyKind::EmptyTeamName, 1),
+                (AnomalyKind::NoLogs, 1),
+                (AnomalyKind::InvalidLogDate, 1),
+                (AnomalyKind::FutureLogDate, 1),
+                (AnomalyKind::AgeOutOfRange, 1),
+                (AnomalyKind::ScoreOutOfRange, 1),
+            ]
+        );
+        assert_eq!(resp.users_with_anomalies, 7);
+        assert_eq!(resp.anomalies[1].sample_ids, vec![users[0].id.clone()]);
+        assert_eq!(resp.anomalies[0].sample_ids, vec![users[7].id.clone()]);
+    }
+}
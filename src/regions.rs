@@ -0,0 +1,246 @@
+use rocket::fairing::AdHoc;
+use rocket::{Build, Rocket};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::CountrySummary;
+use crate::config::{AppConfig, RegionsConfig};
+use crate::countries::{Continent, find_country, normalize_key};
+
+const UNKNOWN_CONTINENT: &str = "Desconhecido";
+const OTHER_REGION: &str = "Outros";
+
+#[derive(FromFormField, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CountryGrouping {
+    Country,
+    Continent,
+    Region,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RegionSummary {
+    region: String,
+    total: usize,
+    countries: Vec<CountrySummary>,
+}
+
+impl RegionSummary {
+    // Linhas do CSV (veja `report.rs`)
+    pub(crate) fn rows(&self) -> Vec<Vec<String>> {
+        self.countries
+            .iter()
+            .map(|c| {
+                vec![
+                    self.region.clone(),
+                    self.total.to_string(),
+                    c.country.clone(),
+                    c.code.clone().unwrap_or_default(),
+                    c.total.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RegionMember {
+    Country(&'static str),
+    Continent(Continent),
+}
+
+/* A hierarquia é país -> continente (tabela embutida) e, por cima,
+ * os grupos do Rocket.toml (LATAM, EMEA, APAC, ...). Um grupo pode
+ * listar países (código ISO) ou continentes inteiros:
+ *
+ *   [default.regions.groups]
+ *   LATAM = ["América do Sul", "MX", "CR", "CU", "DO", "GT", "PA"]
+ *
+ * Se o país estiver listado explicitamente em algum grupo, ele ganha
+ * do continente - assim dá pra tirar o México da América do Norte.
+ */
+pub struct RegionMap {
+    groups: Vec<(String, Vec<RegionMember>)>,
+}
+
+impl RegionMap {
+    pub fn from_config(config: &RegionsConfig) -> Result<Self, String> {
+        let continents: HashMap<String, Continent> = Continent::ALL
+            .into_iter()
+            .map(|c| (normalize_key(c.name()), c))
+            .collect();
+
+        let mut groups = Vec::with_capacity(config.groups.len());
+
+        for (region, members) in config.groups.iter() {
+            let mut resolved = Vec::with_capacity(members.len());
+
+            for member in members {
+                if let Some(continent) = continents.get(&normalize_key(member)) {
+                    resolved.push(RegionMember::Continent(*continent));
+                } else if let Some(info) = find_country(&member.to_uppercase()) {
+                    resolved.push(RegionMember::Country(info.code));
+                } else {
+                    return Err(format!("region '{}': unknown member '{}'", region, member));
+                }
+            }
+
+            groups.push((region.clone(), resolved));
+        }
+
+        Ok(RegionMap { groups })
+    }
+
+    pub fn region_of(&self, code: Option<&str>) -> &str {
+        let Some(info) = code.and_then(find_country) else {
+            return OTHER_REGION;
+        };
+
+        let explicit = self
+            .groups
+            .iter()
+            .find(|(_, members)| members.contains(&RegionMember::Country(info.code)));
+
+        let by_continent = || {
+            self.groups
+                .iter()
+                .find(|(_, members)| members.contains(&RegionMember::Continent(info.continent)))
+        };
+
+        explicit
+            .or_else(by_continent)
+            .map(|(region, _)| region.as_str())
+            .unwrap_or(OTHER_REGION)
+    }
+}
+
+pub fn continent_of(code: Option<&str>) -> &'static str {
+    code.and_then(find_country)
+        .map(|info| info.continent.name())
+        .unwrap_or(UNKNOWN_CONTINENT)
+}
+
+/* Roll-up em cima da contagem por país do `count_countries`:
+ * soma os totais por grupo e mantém os países dentro de cada um.
+ * Mesma ordenação do top-countries (total DESC, nome ASC).
+ */
+pub fn rollup<'a, F>(countries: Vec<CountrySummary>, group_of: F) -> Vec<RegionSummary>
+where
+    F: Fn(Option<&str>) -> &'a str,
+{
+    let mut grouped: HashMap<&str, RegionSummary> = HashMap::new();
+
+    for c in countries {
+        let region = group_of(c.code.as_deref());
+
+        let summary = grouped.entry(region).or_insert_with(|| RegionSummary {
+            region: region.to_owned(),
+            total: 0,
+            countries: Vec::new(),
+        });
+
+        summary.total += c.total;
+        summary.countries.push(c);
+    }
+
+    let mut regions: Vec<RegionSummary> = grouped.into_values().collect();
+    regions.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.region.cmp(&b.region)));
+
+    regions
+}
+
+pub fn region_map_fairing() -> AdHoc {
+    AdHoc::try_on_ignite("Region Map", |rocket: Rocket<Build>| async move {
+        let config = rocket
+            .state::<AppConfig>()
+            .map(|c| c.regions.clone())
+            .unwrap_or_default();
+
+        match RegionMap::from_config(&config) {
+            Ok(regions) => Ok(rocket.manage(regions)),
+            Err(e) => {
+                log::error!("failed to load regions: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn _region_map(groups: &[(&str, &[&str])]) -> Result<RegionMap, String> {
+        let groups: BTreeMap<String, Vec<String>> = groups
+            .iter()
+            .map(|(name, members)| {
+                (
+                    String::from(*name),
+                    members.iter().map(|m| String::from(*m)).collect(),
+                )
+            })
+            .collect();
+
+        RegionMap::from_config(&RegionsConfig { groups })
+    }
+
+    #[test]
+    fn test_region_of() {
+        let regions = _region_map(&[
+            ("APAC", &["Ásia", "Oceania"]),
+            ("LATAM", &["america do sul", "MX"]),
+            ("NA", &["América do Norte"]),
+        ])
+        .unwrap();
+
+        assert_eq!(regions.region_of(Some("BR")), "LATAM");
+        assert_eq!(regions.region_of(Some("MX")), "LATAM");
+        assert_eq!(regions.region_of(Some("US")), "NA");
+        assert_eq!(regions.region_of(Some("JP")), "APAC");
+        assert_eq!(regions.region_of(Some("FR")), OTHER_REGION);
+        assert_eq!(regions.region_of(None), OTHER_REGION);
+
+        assert!(_region_map(&[("EMEA", &["Europa", "Atlântida"])]).is_err());
+    }
+
+    #[test]
+    fn test_rollup_by_continent() {
+        let countries = vec![
+            CountrySummary {
+                country: "Argentina".into(),
+                code: Some("AR".into()),
+                total: 3,
+            },
+            CountrySummary {
+                country: "Japão".into(),
+                code: Some("JP".into()),
+                total: 2,
+            },
+            CountrySummary {
+                country: "Brasil".into(),
+                code: Some("BR".into()),
+                total: 1,
+            },
+            CountrySummary {
+                country: "Atlântida".into(),
+                code: None,
+                total: 1,
+            },
+        ];
+
+        let regions = rollup(countries, continent_of);
+
+        assert_eq!(
+            regions
+                .iter()
+                .map(|r| (r.region.as_str(), r.total, r.countries.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("América do Sul", 4, 2),
+                ("Ásia", 2, 1),
+                (UNKNOWN_CONTINENT, 1, 1)
+            ]
+        );
+    }
+}
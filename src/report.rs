@@ -0,0 +1,395 @@
+use chrono::Local;
+use clap::ValueEnum;
+use rocket::tokio::time::Instant;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::config::AppConfig;
+use crate::countries::CountryRegistry;
+use crate::intern::SymbolTable;
+use crate::parallel::Parallelism;
+use crate::{
+    ActiveUsersResp, Dataset, GetSuperusersResp, TeamInsightsResp, TopCountriesResp, User,
+};
+
+/* Relatórios offline (`challengeresult report ...`).
+ *
+ * Lê o export direto do arquivo, sem subir o Rocket, e passa pelo
+ * mesmo caminho do upload: normaliza os países, monta o `Dataset`
+ * (colunar + cache de agregados) e usa as mesmas agregações dos
+ * handlers. O JSON sai no mesmo formato da API.
+ *
+ *   $ challengeresult report top-countries --input samples/usuarios_10.json
+ *   $ challengeresult report team-insights --input users.json --format csv > teams.csv
+ */
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ReportKind {
+    Superusers,
+    TopCountries,
+    TeamInsights,
+    ActiveUsers,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Table,
+}
+
+/* Linhas e colunas de um relatório, pra virar CSV ou tabela.
+ * As células já vêm como texto.
+ */
+pub struct Table {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+pub trait Tabular {
+    fn table(&self) -> Table;
+}
+
+// RFC 4180: aspas só quando precisa, e aspas internas dobradas
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+impl Table {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+
+        let header: Vec<String> = self.header.iter().map(|h| csv_field(h)).collect();
+        csv.push_str(&header.join(","));
+        csv.push_str("\r\n");
+
+        for row in self.rows.iter() {
+            let row: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+
+        csv
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut widths: Vec<usize> = self.header.iter().map(|h| h.chars().count()).collect();
+        for row in self.rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut text = String::new();
+        let header: Vec<String> = self.header.iter().map(|h| h.to_uppercase()).collect();
+
+        for row in std::iter::once(&header).chain(self.rows.iter()) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            let _ = writeln!(text, "{}", line.join("  ").trim_end());
+        }
+
+        text
+    }
+}
+
+impl Tabular for GetSuperusersResp {
+    fn table(&self) -> Table {
+        Table {
+            // O usuário "achatado": time e listas viram colunas simples
+            header: vec![
+                "id",
+                "name",
+                "age",
+                "score",
+                "active",
+                "country",
+                "country_code",
+                "team",
+                "leader",
+                "projects",
+                "completed_projects",
+                "logs",
+            ],
+            rows: self
+                .data
+                .iter()
+                .map(|u| {
+                    let completed = u.team.projects.iter().filter(|p| p.completed).count();
+
+                    vec![
+                        u.id.clone(),
+                        u.name.clone(),
+                        u.age.to_string(),
+                        u.score.to_string(),
+                        u.active.to_string(),
+                        u.country_name().to_owned(),
+                        u.country_code.clone().unwrap_or_default(),
+                        u.team.name.to_string(),
+                        u.team.leader.to_string(),
+                        u.team.projects.len().to_string(),
+                        completed.to_string(),
+                        u.logs.len().to_string(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Tabular for TopCountriesResp {
+    fn table(&self) -> Table {
+        // `?group=`: uma linha por país dentro de cada região
+        if !self.regions.is_empty() {
+            return Table {
+                header: vec!["region", "region_total", "country", "code", "total"],
+                rows: self.regions.iter().flat_map(|r| r.rows()).collect(),
+            };
+        }
+
+        Table {
+            header: vec!["country", "code", "total"],
+            rows: self
+                .countries
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.country.clone(),
+                        c.code.clone().unwrap_or_default(),
+                        c.total.to_string(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Tabular for TeamInsightsResp {
+    fn table(&self) -> Table {
+        Table {
+            header: vec![
+                "team",
+                "total_members",
+                "leaders",
+                "completed_projects",
+                "active_percentage",
+            ],
+            rows: self
+                .teams
+                .iter()
+                .map(|t| {
+                    vec![
+                        t.team.clone(),
+                        t.total_members.to_string(),
+                        t.leaders.to_string(),
+                        t.completed_projects.to_string(),
+                        t.active_percentage.to_string(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Tabular for ActiveUsersResp {
+    fn table(&self) -> Table {
+        Table {
+            header: vec!["date", "total"],
+            rows: self
+                .logins
+                .iter()
+                .map(|l| vec![l.date.clone(), l.total.to_string()])
+                .collect(),
+        }
+    }
+}
+
+fn render<T: Serialize + Tabular>(resp: &T, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(resp).unwrap() + "\n",
+        OutputFormat::Csv => resp.table().to_csv(),
+        OutputFormat::Table => resp.table().to_text(),
+    }
+}
+
+fn build_report(
+    kind: ReportKind,
+    dataset: &Dataset,
+    parallelism: &Parallelism,
+    min: Option<u16>,
+    format: OutputFormat,
+) -> String {
+    let start_time = Instant::now();
+    let timestamp = format!("{:?}", Local::now());
+
+    match kind {
+        ReportKind::Superusers => {
+            let data = dataset.superusers();
+
+            let resp = GetSuperusersResp {
+                timestamp,
+                execution_time_ms: start_time.elapsed().as_millis(),
+                user_count: data.len(),
+                data,
+            };
+            render(&resp, format)
+        }
+        ReportKind::TopCountries => {
+            let countries = dataset.top_countries(parallelism);
+
+            let resp = TopCountriesResp {
+                timestamp,
+                execution_time_ms: start_time.elapsed().as_millis(),
+                countries,
+                regions: Vec::new(),
+            };
+            render(&resp, format)
+        }
+        ReportKind::TeamInsights => {
+            let resp = TeamInsightsResp {
+                teams: dataset.aggregates(parallelism).teams.clone(),
+                timestamp,
+                execution_time_ms: start_time.elapsed().as_millis(),
+            };
+            render(&resp, format)
+        }
+        ReportKind::ActiveUsers => {
+            let logins = dataset.active_users(parallelism, min);
+
+            let resp = ActiveUsersResp {
+                logins,
+                timestamp,
+                execution_time_ms: start_time.elapsed().as_millis(),
+            };
+            render(&resp, format)
+        }
+    }
+}
+
+/* A config vem do mesmo lugar que a do servidor (Rocket.toml +
+ * variáveis ROCKET_*), então aliases de país e o pool de threads
+ * são os mesmos.
+ */
+pub fn run(
+    kind: ReportKind,
+    input: &Path,
+    format: OutputFormat,
+    min: Option<u16>,
+) -> Result<String, String> {
+    let config = AppConfig::from_rocket_figment()?;
+
+    let countries = match &config.countries.alias_file {
+        Some(path) => CountryRegistry::builtin().with_alias_file(path)?,
+        None => CountryRegistry::builtin(),
+    };
+    let parallelism = Parallelism::from_config(&config.parallel)?;
+
+    let content =
+        std::fs::read_to_string(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+    let (users, symbols) = SymbolTable::parsing(|| serde_json::from_str::<Vec<User>>(&content));
+    let mut users = users.map_err(|e| format!("{}: {}", input.display(), e))?;
+
+    countries.normalize(&mut users);
+
+    let dataset = Dataset::new(1, users, symbols);
+
+    Ok(build_report(kind, &dataset, &parallelism, min, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::_load_sample;
+
+    fn _dataset() -> Dataset {
+        let mut users: Vec<User> = serde_json::from_str(&_load_sample("usuarios_10")).unwrap();
+        CountryRegistry::builtin().normalize(&mut users);
+
+        let symbols = crate::collect_symbols(&mut users);
+        Dataset::new(1, users, symbols)
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("Brasil"), "Brasil");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_build_report() {
+        let dataset = _dataset();
+        let parallelism = Parallelism::from_config(&Default::default()).unwrap();
+
+        // Valores contados na mão no samples/usuarios_10.json
+        let csv = build_report(
+            ReportKind::TopCountries,
+            &dataset,
+            &parallelism,
+            None,
+            OutputFormat::Csv,
+        );
+        assert_eq!(
+            csv,
+            "country,code,total\r\n\
+             Argentina,AR,3\r\n\
+             Canadá,CA,2\r\n\
+             Japão,JP,2\r\n\
+             Brasil,BR,1\r\n\
+             França,FR,1\r\n"
+        );
+
+        let json = build_report(
+            ReportKind::Superusers,
+            &dataset,
+            &parallelism,
+            None,
+            OutputFormat::Json,
+        );
+        let resp: GetSuperusersResp = serde_json::from_str(&json).unwrap();
+        assert_eq!(resp.user_count, 1);
+        assert_eq!(resp.data[0].id, "c460b871-77ec-46f1-9127-22ea6989b0bc");
+        assert_eq!(resp.data[0].score, 1040);
+
+        let json = build_report(
+            ReportKind::TeamInsights,
+            &dataset,
+            &parallelism,
+            None,
+            OutputFormat::Json,
+        );
+        let resp: TeamInsightsResp = serde_json::from_str(&json).unwrap();
+        let members: usize = resp.teams.iter().map(|t| t.total_members).sum();
+        assert_eq!(members, 10);
+
+        let text = build_report(
+            ReportKind::ActiveUsers,
+            &dataset,
+            &parallelism,
+            Some(6),
+            OutputFormat::Table,
+        );
+        assert_eq!(
+            text,
+            "DATE        TOTAL\n2025-03-25  6\n2025-03-26  6\n2025-03-31  6\n"
+        );
+
+        let text = build_report(
+            ReportKind::ActiveUsers,
+            &dataset,
+            &parallelism,
+            Some(u16::MAX),
+            OutputFormat::Table,
+        );
+        assert_eq!(text, "DATE  TOTAL\n");
+    }
+}
@@ -0,0 +1,281 @@
+use rocket::data::{self, Data, FromData, Limits};
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::figment::providers::{Format, Toml};
+use rocket::http::Status;
+use rocket::{Build, Request, Rocket};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::config::AppConfig;
+
+/* Cenários da avaliação.
+ *
+ * Antes cada endpoint tinha a sua função `evaluate_*` copiada e colada.
+ * Agora um cenário é só dado - path, query, status esperado, o tipo de
+ * resposta pra validar e um orçamento de latência - e um checker
+ * genérico (`evaluation.rs`) roda todos. Eles podem vir de um arquivo
+ * TOML ou JSON:
+ *
+ *   [[scenarios]]
+ *   path = "/active-users-per-day"
+ *   query = { min = 3 }
+ *   response = "active_users"
+ *   latency_budget_ms = 200
+ *   verify = false
+ *
+ * Sem arquivo, vale a lista embutida com os 4 endpoints do desafio.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseKind {
+    Superusers,
+    TopCountries,
+    TeamInsights,
+    ActiveUsers,
+    Leaderboard,
+    DataQuality,
+    UsersDiff,
+    Memory,
+    // Qualquer JSON válido
+    Json,
+    // Não olha o body, só status e latência
+    Any,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Scenario {
+    // Padrão: o path (+ query)
+    #[serde(default)]
+    pub name: Option<String>,
+    pub path: String,
+    #[serde(default)]
+    pub query: BTreeMap<String, serde_json::Value>,
+    #[serde(default = "default_status")]
+    pub expected_status: u16,
+    #[serde(default = "default_response")]
+    pub response: ResponseKind,
+    #[serde(default)]
+    pub latency_budget_ms: Option<u64>,
+    // Confere com o gabarito (`correctness.rs`). Desligue quando a
+    // query muda o resultado (ex: `?min=`), senão vai dar mismatch.
+    #[serde(default = "default_verify")]
+    pub verify: bool,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+fn default_response() -> ResponseKind {
+    ResponseKind::Json
+}
+
+fn default_verify() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ScenarioFile {
+    scenarios: Vec<Scenario>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScenarioFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenarios(pub Vec<Scenario>);
+
+impl Scenario {
+    pub fn new(path: &str, response: ResponseKind) -> Self {
+        Scenario {
+            name: None,
+            path: path.to_owned(),
+            query: BTreeMap::new(),
+            expected_status: default_status(),
+            response,
+            latency_budget_ms: None,
+            verify: default_verify(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+
+        match self.query_string() {
+            query if query.is_empty() => self.path.clone(),
+            query => format!("{}?{}", self.path, query),
+        }
+    }
+
+    fn query_string(&self) -> String {
+        let mut query = reqwest::Url::parse("http://localhost").unwrap();
+
+        for (key, value) in self.query.iter() {
+            // `min = 3` no TOML vira número; na URL é tudo texto
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            query.query_pairs_mut().append_pair(key, &value);
+        }
+
+        query.query().unwrap_or_default().to_owned()
+    }
+
+    pub fn url(&self, base_url: &str) -> String {
+        match self.query_string() {
+            query if query.is_empty() => format!("{}{}", base_url, self.path),
+            query => format!("{}{}?{}", base_url, self.path, query),
+        }
+    }
+}
+
+impl Scenarios {
+    pub fn builtin() -> Self {
+        Scenarios(vec![
+            Scenario::new("/superusers", ResponseKind::Superusers),
+            Scenario::new("/top-countries", ResponseKind::TopCountries),
+            Scenario::new("/team-insights", ResponseKind::TeamInsights),
+            Scenario::new("/active-users-per-day", ResponseKind::ActiveUsers),
+        ])
+    }
+
+    pub fn parse(text: &str, format: ScenarioFormat) -> Result<Self, String> {
+        let file: ScenarioFile = match format {
+            ScenarioFormat::Toml => Figment::from(Toml::string(text))
+                .extract()
+                .map_err(|e| e.to_string())?,
+            ScenarioFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string())?,
+        };
+
+        Self::validate(file.scenarios)
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+        let format = match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("json") => ScenarioFormat::Json,
+            _ => ScenarioFormat::Toml,
+        };
+
+        Self::parse(&text, format).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    fn validate(scenarios: Vec<Scenario>) -> Result<Self, String> {
+        if scenarios.is_empty() {
+            return Err(String::from("no scenarios defined"));
+        }
+
+        let mut names = HashSet::new();
+
+        for scenario in scenarios.iter() {
+            if !scenario.path.starts_with('/') {
+                return Err(format!(
+                    "scenario '{}': path must start with '/'",
+                    scenario.path
+                ));
+            }
+
+            // O nome é a chave do relatório, então não pode repetir
+            if !names.insert(scenario.name()) {
+                return Err(format!("duplicated scenario '{}'", scenario.name()));
+            }
+        }
+
+        Ok(Scenarios(scenarios))
+    }
+}
+
+// Cenários no corpo do `POST /evaluation`
+#[rocket::async_trait]
+impl<'r> FromData<'r> for Scenarios {
+    type Error = String;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = req.limits().get("string").unwrap_or(Limits::STRING);
+
+        let text = match data.open(limit).into_string().await {
+            Ok(text) if text.is_complete() => text.into_inner(),
+            Ok(_) => {
+                let error = format!("scenario file larger than {}", limit);
+                return data::Outcome::Error((Status::PayloadTooLarge, error));
+            }
+            Err(e) => return data::Outcome::Error((Status::BadRequest, e.to_string())),
+        };
+
+        let format = match req.content_type() {
+            Some(ct) if ct.is_json() => ScenarioFormat::Json,
+            _ => ScenarioFormat::Toml,
+        };
+
+        match Scenarios::parse(&text, format) {
+            Ok(scenarios) => data::Outcome::Success(scenarios),
+            Err(e) => data::Outcome::Error((Status::BadRequest, e)),
+        }
+    }
+}
+
+pub fn scenarios_fairing() -> AdHoc {
+    AdHoc::try_on_ignite("Evaluation Scenarios", |rocket: Rocket<Build>| async move {
+        let scenarios_file = rocket
+            .state::<AppConfig>()
+            .and_then(|c| c.evaluation.scenarios_file.clone());
+
+        let scenarios = match scenarios_file {
+            Some(path) => Scenarios::from_file(&path),
+            None => Ok(Scenarios::builtin()),
+        };
+
+        match scenarios {
+            Ok(scenarios) => Ok(rocket.manage(scenarios)),
+            Err(e) => {
+                log::error!("failed to load evaluation scenarios: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenarios() {
+        let scenarios = Scenarios::from_file("samples/scenarios.toml").unwrap();
+
+        assert_eq!(scenarios.0.len(), 7);
+        assert_eq!(
+            scenarios.0[4].url("http://127.0.0.1:8000"),
+            "http://127.0.0.1:8000/active-users-per-day?min=3"
+        );
+        assert_eq!(scenarios.0[4].name(), "/active-users-per-day?min=3");
+
+        let json = r#"{ "scenarios": [
+            { "name": "diff", "path": "/users/diff", "expected_status": 404, "response": "any" }
+        ] }"#;
+        let scenarios = Scenarios::parse(json, ScenarioFormat::Json).unwrap();
+
+        assert_eq!(scenarios.0[0].name(), "diff");
+        assert_eq!(scenarios.0[0].expected_status, 404);
+        assert!(scenarios.0[0].verify);
+
+        let duplicated = r#"
+            [[scenarios]]
+            path = "/superusers"
+
+            [[scenarios]]
+            path = "/superusers"
+        "#;
+        assert!(Scenarios::parse(duplicated, ScenarioFormat::Toml).is_err());
+        assert!(Scenarios::parse("scenarios = []", ScenarioFormat::Toml).is_err());
+    }
+}